@@ -0,0 +1,19 @@
+use anchor_lang::prelude::Pubkey;
+
+pub const ANCHOR_DISCRIMINATOR: usize = 8;
+
+/// Vesting accounts derived from `[b"vesting", creator, company_name]`. Legacy accounts,
+/// derived from `[company_name]` alone, predate the version field.
+pub const VESTING_VERSION: u8 = 2;
+
+/// How long a migrated vesting account keeps its legacy treasury reserved for legacy
+/// grants that have not been migrated yet, in seconds.
+pub const LEGACY_MIGRATION_WINDOW: i64 = 365 * 24 * 60 * 60;
+
+pub const MAX_MILESTONES: usize = 8;
+
+pub const MAX_ADMINS: usize = 10;
+
+/// Stands in for the mint of vesting accounts that pay out native SOL from a
+/// lamport treasury.
+pub const NATIVE_SOL_MINT: Pubkey = anchor_lang::system_program::ID;
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Claim not available yet")]
+    ClaimNotAvailableYet,
+
+    #[msg("Invalid vesting period")]
+    InvalidVestingPeriod,
+
+    #[msg("Calculation overflow")]
+    CalculationOverflow,
+
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+
+    #[msg("Vesting has already been revoked")]
+    AlreadyRevoked,
+
+    #[msg("Cliff time is before the start time")]
+    CliffBeforeStart,
+
+    #[msg("Cliff time is after the end time")]
+    CliffAfterEnd,
+
+    #[msg("Grant amount must be greater than zero")]
+    InvalidAmount,
+
+    #[msg("Treasury balance cannot cover the grant")]
+    InsufficientTreasuryBalance,
+
+    #[msg("Step period must be positive and fit within the vesting period")]
+    InvalidStepPeriod,
+
+    #[msg("Amount exceeds the unallocated treasury balance")]
+    InsufficientUnallocatedBalance,
+
+    #[msg("New beneficiary must differ from the current one")]
+    SameBeneficiary,
+
+    #[msg("Grant id does not match the beneficiary's next grant id")]
+    InvalidGrantId,
+
+    #[msg("Account is not a grant of this beneficiary and vesting account")]
+    InvalidGrantAccount,
+
+    #[msg("Grant still has tokens left to vest or claim")]
+    GrantNotSettled,
+
+    #[msg("Vesting account still has open grants")]
+    GrantsStillOpen,
+
+    #[msg("Treasury still holds tokens")]
+    TreasuryNotEmpty,
+
+    #[msg("Signer is not allowed to perform this action")]
+    Unauthorized,
+
+    #[msg("Account was not migrated into this vesting account")]
+    NotMigrationSource,
+
+    #[msg("Non-transferable mints cannot be vested")]
+    NonTransferableMint,
+
+    #[msg("Mint uses a Token-2022 extension that vesting does not support")]
+    UnsupportedMintExtension,
+
+    #[msg("Caller is not allowed to claim for this beneficiary")]
+    ClaimNotDelegated,
+
+    #[msg("Milestone tranches must be non-zero, at most MAX_MILESTONES and add up to the total amount")]
+    InvalidMilestones,
+
+    #[msg("Grant does not vest by milestones")]
+    NotMilestoneGrant,
+
+    #[msg("Milestone does not exist")]
+    InvalidMilestoneIndex,
+
+    #[msg("Milestone has already been attested")]
+    MilestoneAlreadyAttested,
+
+    #[msg("Vesting account is governed by its admins; submit a proposal instead")]
+    MultisigRequired,
+
+    #[msg("Vesting account has no admin set")]
+    NotMultisig,
+
+    #[msg(
+        "Admins must be unique and at most MAX_ADMINS, with a threshold between 1 and their number"
+    )]
+    InvalidAdminSet,
+
+    #[msg("Signer is not an admin of this vesting account")]
+    NotAdmin,
+
+    #[msg("Admin has already approved this proposal")]
+    AlreadyApproved,
+
+    #[msg("Proposal does not have enough admin approvals")]
+    NotEnoughApprovals,
+
+    #[msg("Proposal action does not match the accounts of this instruction")]
+    ProposalActionMismatch,
+
+    #[msg("Batch must hold at least one entry and a grant counter and grant account per entry")]
+    InvalidBatch,
+
+    #[msg("Grant account already exists")]
+    GrantAlreadyExists,
+
+    #[msg("Signer is not the pending owner of this vesting account")]
+    NotPendingOwner,
+
+    #[msg("No owner transfer is pending")]
+    NoPendingOwner,
+
+    #[msg("Vesting account is paused")]
+    VestingPaused,
+
+    #[msg("Not available for native SOL vesting accounts")]
+    NativeSolUnsupported,
+
+    #[msg("Remaining account is not a grant counter of this vesting account")]
+    InvalidLeftoverAccount,
+
+    #[msg("Legacy grants can still be migrated against the reserved legacy treasury")]
+    LegacyGrantsPending,
+
+    #[msg("Vesting account still has proposals that were neither executed nor cancelled")]
+    ProposalsStillOpen,
+}
@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+
+/// Emits `$event` for indexers.
+///
+/// Built with the `event-cpi` feature, the event is recorded through a self-CPI (which
+/// requires the instruction's accounts to carry `#[event_cpi]`) so it survives log
+/// truncation; otherwise it is written to the program logs with `emit!`.
+#[macro_export]
+macro_rules! emit_event {
+    ($ctx:expr, $event:expr) => {{
+        #[cfg(feature = "event-cpi")]
+        {
+            let ctx = &$ctx;
+            anchor_lang::prelude::emit_cpi!($event);
+        }
+        #[cfg(not(feature = "event-cpi"))]
+        {
+            anchor_lang::prelude::emit!($event);
+        }
+    }};
+}
+
+#[event]
+pub struct VestingAccountCreated {
+    pub vesting_account: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub company_name: String,
+}
+
+#[event]
+pub struct GrantCreated {
+    pub vesting_account: Pubkey,
+    pub employee_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub grant_id: u64,
+    pub total_amount: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub cliff_time: i64,
+}
+
+#[event]
+pub struct TokensClaimed {
+    pub vesting_account: Pubkey,
+    pub employee_account: Pubkey,
+    pub beneficiary: Pubkey,
+    /// Tokens debited from the grant, including any Token-2022 transfer fee.
+    pub amount: u64,
+    /// Part of `amount` withheld by the mint's transfer fee.
+    pub transfer_fee: u64,
+    pub total_withdrawn: u64,
+}
+
+#[event]
+pub struct GrantRevoked {
+    pub vesting_account: Pubkey,
+    pub employee_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub revoked_at: i64,
+    /// Unvested tokens returned to the owner.
+    pub returned_amount: u64,
+}
+
+#[event]
+pub struct MilestoneAttested {
+    pub vesting_account: Pubkey,
+    pub employee_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub milestone_index: u8,
+    pub amount: u64,
+    pub attested_at: i64,
+}
+
+#[event]
+pub struct TreasuryFunded {
+    pub vesting_account: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub total_deposited: u64,
+}
+
+#[event]
+pub struct UnallocatedWithdrawn {
+    pub vesting_account: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BeneficiaryTransferred {
+    pub vesting_account: Pubkey,
+    pub previous_beneficiary: Pubkey,
+    pub new_beneficiary: Pubkey,
+    pub employee_account: Pubkey,
+}
+
+#[event]
+pub struct AdminsUpdated {
+    pub vesting_account: Pubkey,
+    pub admins: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub vesting_account: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+}
+
+#[event]
+pub struct ProposalApproved {
+    pub vesting_account: Pubkey,
+    pub proposal: Pubkey,
+    pub admin: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub vesting_account: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+}
+
+#[event]
+pub struct ProposalCancelled {
+    pub vesting_account: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+}
+
+#[event]
+pub struct OwnerTransferProposed {
+    pub vesting_account: Pubkey,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+}
+
+#[event]
+pub struct OwnerTransferAccepted {
+    pub vesting_account: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct OwnerTransferCancelled {
+    pub vesting_account: Pubkey,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub vesting_account: Pubkey,
+    pub paused: bool,
+}
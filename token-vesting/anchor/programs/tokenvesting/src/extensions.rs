@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::{
+        spl_token_2022::{
+            extension::{
+                transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+                BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+            },
+            state::{Account as TokenAccountState, Mint as MintState},
+        },
+        Token2022,
+    },
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::error::ErrorCode;
+
+/// Rejects Token-2022 mints whose extensions break vesting.
+///
+/// Non-transferable mints can never leave the treasury, a permanent delegate could drain
+/// it, and confidential transfers hide the balances the committed-amount checks rely on.
+/// Transfer fees are supported and reported on each claim, and transfer hooks receive
+/// their extra accounts through the remaining accounts of every instruction that moves
+/// tokens.
+pub fn validate_mint(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != Token2022::id() {
+        return Ok(());
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+    for extension in mint_state.get_extension_types()? {
+        match extension {
+            ExtensionType::NonTransferable => return Err(ErrorCode::NonTransferableMint.into()),
+            ExtensionType::PermanentDelegate
+            | ExtensionType::ConfidentialTransferMint
+            | ExtensionType::ConfidentialTransferFeeConfig => {
+                return Err(ErrorCode::UnsupportedMintExtension.into())
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Fee the mint withholds when `amount` is transferred in the current epoch.
+pub fn transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != Token2022::id() {
+        return Ok(0);
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+    let Ok(transfer_fee_config) = mint_state.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+
+    transfer_fee_config
+        .calculate_epoch_fee(Clock::get()?.epoch, amount)
+        .ok_or_else(|| ErrorCode::CalculationOverflow.into())
+}
+
+/// Transfer fees withheld in `token_account`, which Token-2022 keeps from closing it until
+/// they are harvested to the mint.
+pub fn withheld_transfer_fees(token_account: &InterfaceAccount<TokenAccount>) -> Result<u64> {
+    let account_info = token_account.to_account_info();
+    if *account_info.owner != Token2022::id() {
+        return Ok(0);
+    }
+
+    let account_data = account_info.try_borrow_data()?;
+    let account_state = StateWithExtensions::<TokenAccountState>::unpack(&account_data)?;
+    Ok(account_state
+        .get_extension::<TransferFeeAmount>()
+        .map_or(0, |transfer_fee_amount| {
+            transfer_fee_amount.withheld_amount.into()
+        }))
+}
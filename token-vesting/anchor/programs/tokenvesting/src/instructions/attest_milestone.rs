@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    emit_event, error::ErrorCode, events::MilestoneAttested, EmployeeAccount, VestingAccount,
+    VestingSchedule,
+};
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AttestMilestone<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner,
+        constraint = vesting_account.is_single_owner() @ ErrorCode::MultisigRequired,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"employee_vesting", employee_account.beneficiary.as_ref(), vesting_account.key().as_ref(), employee_account.grant_id.to_le_bytes().as_ref()],
+        bump = employee_account.bump,
+        has_one = vesting_account,
+    )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+}
+
+/// Releases one tranche of a milestone grant. The tranche becomes claimable once
+/// the grant's time floor has passed.
+pub fn attest_grant_milestone(ctx: Context<AttestMilestone>, milestone_index: u8) -> Result<()> {
+    let event = record_attestation(&mut ctx.accounts.employee_account, milestone_index)?;
+
+    emit_event!(ctx, event);
+    Ok(())
+}
+
+/// Marks tranche `milestone_index` of a milestone grant as attested now.
+pub fn record_attestation(
+    employee_account: &mut Account<EmployeeAccount>,
+    milestone_index: u8,
+) -> Result<MilestoneAttested> {
+    require!(
+        employee_account.schedule == VestingSchedule::Milestone,
+        ErrorCode::NotMilestoneGrant
+    );
+    require!(
+        employee_account.revoked_at.is_none(),
+        ErrorCode::AlreadyRevoked
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let milestone = employee_account
+        .milestones
+        .get_mut(milestone_index as usize)
+        .ok_or(ErrorCode::InvalidMilestoneIndex)?;
+    require!(
+        milestone.attested_at.is_none(),
+        ErrorCode::MilestoneAlreadyAttested
+    );
+    milestone.attested_at = Some(now);
+    let amount = milestone.amount;
+
+    Ok(MilestoneAttested {
+        vesting_account: employee_account.vesting_account,
+        employee_account: employee_account.key(),
+        beneficiary: employee_account.beneficiary,
+        milestone_index,
+        amount,
+        attested_at: now,
+    })
+}
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    emit_event, error::ErrorCode, events::TokensClaimed, extensions::transfer_fee, EmployeeAccount,
+    VestingAccount,
+};
+
+use super::transfer_from_treasury;
+
+/// Settles every grant passed as a writable remaining account in one instruction.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ClaimAll<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = treasury_token_account,
+        has_one = mint,
+        constraint = !vesting_account.paused @ ErrorCode::VestingPaused,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(mut)]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        associated_token::mint = mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program,
+    )]
+    pub employee_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// The first `grant_count` remaining accounts are the grants to settle; any after them are
+/// forwarded to the token program for transfer-hook mints.
+pub fn claim_all_grants<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimAll<'info>>,
+    grant_count: u8,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let beneficiary = ctx.accounts.beneficiary.key();
+    let vesting_account_key = ctx.accounts.vesting_account.key();
+
+    require!(
+        usize::from(grant_count) <= ctx.remaining_accounts.len(),
+        ErrorCode::InvalidGrantAccount
+    );
+    let (grant_accounts, transfer_hook_accounts) =
+        ctx.remaining_accounts.split_at(usize::from(grant_count));
+
+    let mut total_claimed: u64 = 0;
+    for account_info in grant_accounts {
+        require!(account_info.is_writable, ErrorCode::InvalidGrantAccount);
+        let mut employee_account = Account::<EmployeeAccount>::try_from(account_info)?;
+        require_keys_eq!(
+            employee_account.beneficiary,
+            beneficiary,
+            ErrorCode::InvalidGrantAccount
+        );
+        require_keys_eq!(
+            employee_account.vesting_account,
+            vesting_account_key,
+            ErrorCode::InvalidGrantAccount
+        );
+
+        let vested_amount = employee_account.vested_amount(now)?;
+        let claimable_amount = vested_amount.saturating_sub(employee_account.total_withdrawn);
+        if claimable_amount == 0 {
+            continue;
+        }
+
+        let fee = transfer_fee(&ctx.accounts.mint, claimable_amount)?;
+        employee_account.total_withdrawn = employee_account
+            .total_withdrawn
+            .saturating_add(claimable_amount);
+        employee_account.total_transfer_fees =
+            employee_account.total_transfer_fees.saturating_add(fee);
+        // Written back immediately so a grant listed twice is only paid once.
+        employee_account.exit(&crate::ID)?;
+
+        // One transfer per grant keeps each grant's transfer fee exact.
+        transfer_from_treasury(
+            &ctx.accounts.vesting_account,
+            &ctx.accounts.treasury_token_account,
+            &ctx.accounts.employee_token_account,
+            claimable_amount,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            transfer_hook_accounts,
+        )?;
+
+        total_claimed = total_claimed
+            .checked_add(claimable_amount)
+            .ok_or(ErrorCode::CalculationOverflow)?;
+
+        emit_event!(
+            ctx,
+            TokensClaimed {
+                vesting_account: vesting_account_key,
+                employee_account: employee_account.key(),
+                beneficiary,
+                amount: claimable_amount,
+                transfer_fee: fee,
+                total_withdrawn: employee_account.total_withdrawn,
+            }
+        );
+    }
+
+    if total_claimed == 0 {
+        return Err(ErrorCode::NothingToClaim.into());
+    }
+
+    let vesting_account = &mut ctx.accounts.vesting_account;
+    vesting_account.committed_amount = vesting_account
+        .committed_amount
+        .saturating_sub(total_claimed);
+    Ok(())
+}
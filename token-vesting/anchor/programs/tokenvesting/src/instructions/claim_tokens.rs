@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    emit_event, error::ErrorCode, events::TokensClaimed, extensions::transfer_fee, EmployeeAccount,
+    VestingAccount,
+};
+
+use super::transfer_from_treasury;
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ClaimTokens<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"employee_vesting", beneficiary.key().as_ref(), vesting_account.key().as_ref(), employee_account.grant_id.to_le_bytes().as_ref()],
+        bump = employee_account.bump,
+        has_one = beneficiary,
+        has_one = vesting_account,
+    )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+
+    #[account(
+        mut,
+        has_one = treasury_token_account,
+        has_one = mint,
+        constraint = !vesting_account.paused @ ErrorCode::VestingPaused,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(mut)]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        associated_token::mint = mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program,
+    )]
+    pub employee_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Remaining accounts are forwarded to the token program for transfer-hook mints.
+pub fn claim_vested_tokens<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimTokens<'info>>,
+) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let event = pay_vested_tokens(
+        &mut accounts.employee_account,
+        &mut accounts.vesting_account,
+        &accounts.treasury_token_account,
+        &accounts.employee_token_account,
+        &accounts.mint,
+        &accounts.token_program,
+        ctx.remaining_accounts,
+    )?;
+
+    emit_event!(ctx, event);
+    Ok(())
+}
+
+/// Pays everything vested and not yet withdrawn into `employee_token_account`.
+pub fn pay_vested_tokens<'info>(
+    employee_account: &mut Account<'info, EmployeeAccount>,
+    vesting_account: &mut Account<'info, VestingAccount>,
+    treasury_token_account: &InterfaceAccount<'info, TokenAccount>,
+    employee_token_account: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    additional_accounts: &[AccountInfo<'info>],
+) -> Result<TokensClaimed> {
+    let claimable_amount = withdraw_vested_amount(
+        employee_account,
+        vesting_account,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    let fee = transfer_fee(mint, claimable_amount)?;
+    employee_account.total_transfer_fees = employee_account.total_transfer_fees.saturating_add(fee);
+
+    transfer_from_treasury(
+        vesting_account,
+        treasury_token_account,
+        employee_token_account,
+        claimable_amount,
+        mint,
+        token_program,
+        additional_accounts,
+    )?;
+
+    Ok(TokensClaimed {
+        vesting_account: vesting_account.key(),
+        employee_account: employee_account.key(),
+        beneficiary: employee_account.beneficiary,
+        amount: claimable_amount,
+        transfer_fee: fee,
+        total_withdrawn: employee_account.total_withdrawn,
+    })
+}
+
+/// Books everything vested at `now` and not yet withdrawn as withdrawn, returning the
+/// amount to pay out.
+pub fn withdraw_vested_amount(
+    employee_account: &mut EmployeeAccount,
+    vesting_account: &mut VestingAccount,
+    now: i64,
+) -> Result<u64> {
+    if now < employee_account.cliff_time {
+        return Err(ErrorCode::ClaimNotAvailableYet.into());
+    }
+
+    let vested_amount = employee_account.vested_amount(now)?;
+    let claimable_amount = vested_amount.saturating_sub(employee_account.total_withdrawn);
+
+    if claimable_amount == 0 {
+        return Err(ErrorCode::NothingToClaim.into());
+    }
+
+    employee_account.total_withdrawn = employee_account
+        .total_withdrawn
+        .saturating_add(claimable_amount);
+
+    vesting_account.committed_amount = vesting_account
+        .committed_amount
+        .saturating_sub(claimable_amount);
+    Ok(claimable_amount)
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, EmployeeAccount, VestingAccount};
+
+#[derive(Accounts)]
+pub struct CloseEmployeeAccount<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"employee_vesting", employee_account.beneficiary.as_ref(), vesting_account.key().as_ref(), employee_account.grant_id.to_le_bytes().as_ref()],
+        bump = employee_account.bump,
+        has_one = payer,
+        has_one = vesting_account,
+    )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+}
+
+pub fn close_settled_grant(ctx: Context<CloseEmployeeAccount>) -> Result<()> {
+    require!(
+        ctx.accounts.employee_account.is_settled()?,
+        ErrorCode::GrantNotSettled
+    );
+
+    let vesting_account = &mut ctx.accounts.vesting_account;
+    vesting_account.active_grants = vesting_account.active_grants.saturating_sub(1);
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{error::ErrorCode, VestingAccount};
+
+use super::{close_leftover_accounts, close_treasury};
+
+/// Open to the owner in multisig mode too: it only runs once every grant and proposal is
+/// closed and the treasury is empty, so it moves nothing but rent.
+#[derive(Accounts)]
+pub struct CloseVestingAccount<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        has_one = treasury_token_account,
+        has_one = mint,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(mut)]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the transfer fees still withheld in the treasury.
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Remaining accounts are the grant counters to close along with the vesting account, see
+/// `close_leftover_accounts`.
+pub fn close_empty_vesting_account<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseVestingAccount<'info>>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.vesting_account.active_grants == 0,
+        ErrorCode::GrantsStillOpen
+    );
+    require!(
+        ctx.accounts.vesting_account.open_proposals == 0,
+        ErrorCode::ProposalsStillOpen
+    );
+    require!(
+        ctx.accounts.vesting_account.legacy_reserved == 0,
+        ErrorCode::LegacyGrantsPending
+    );
+    require!(
+        ctx.accounts.treasury_token_account.amount == 0,
+        ErrorCode::TreasuryNotEmpty
+    );
+
+    close_treasury(
+        &ctx.accounts.vesting_account,
+        &ctx.accounts.treasury_token_account,
+        &ctx.accounts.mint,
+        ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.token_program,
+    )?;
+    close_leftover_accounts(
+        ctx.accounts.vesting_account.key(),
+        &ctx.accounts.owner.to_account_info(),
+        ctx.remaining_accounts,
+    )
+}
@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{emit_event, error::ErrorCode, ClaimPermission, EmployeeAccount, VestingAccount};
+
+use super::pay_vested_tokens;
+
+/// Lets the beneficiary choose who may claim on their behalf. Native SOL grants have no
+/// crank, so only their beneficiary claims.
+#[derive(Accounts)]
+pub struct SetClaimDelegate<'info> {
+    pub beneficiary: Signer<'info>,
+
+    #[account(constraint = !vesting_account.is_native_sol() @ ErrorCode::NativeSolUnsupported)]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"employee_vesting", beneficiary.key().as_ref(), vesting_account.key().as_ref(), employee_account.grant_id.to_le_bytes().as_ref()],
+        bump = employee_account.bump,
+        has_one = beneficiary,
+        has_one = vesting_account,
+    )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+}
+
+/// Claims on behalf of the beneficiary. The tokens always go to the beneficiary's
+/// associated token account; the caller only pays the fees.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CrankClaim<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    pub beneficiary: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"employee_vesting", beneficiary.key().as_ref(), vesting_account.key().as_ref(), employee_account.grant_id.to_le_bytes().as_ref()],
+        bump = employee_account.bump,
+        has_one = beneficiary,
+        has_one = vesting_account,
+    )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+
+    #[account(
+        mut,
+        has_one = treasury_token_account,
+        has_one = mint,
+        constraint = !vesting_account.paused @ ErrorCode::VestingPaused,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(mut)]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program,
+    )]
+    pub employee_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn save_claim_permission(
+    ctx: Context<SetClaimDelegate>,
+    claim_permission: ClaimPermission,
+) -> Result<()> {
+    ctx.accounts.employee_account.claim_permission = claim_permission;
+    Ok(())
+}
+
+/// Remaining accounts are forwarded to the token program for transfer-hook mints.
+pub fn claim_for_beneficiary<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CrankClaim<'info>>,
+) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let caller = accounts.caller.key();
+    let allowed = caller == accounts.beneficiary.key()
+        || match accounts.employee_account.claim_permission {
+            ClaimPermission::BeneficiaryOnly => false,
+            ClaimPermission::Delegate { delegate } => caller == delegate,
+            ClaimPermission::Permissionless => true,
+        };
+    require!(allowed, ErrorCode::ClaimNotDelegated);
+
+    let event = pay_vested_tokens(
+        &mut accounts.employee_account,
+        &mut accounts.vesting_account,
+        &accounts.treasury_token_account,
+        &accounts.employee_token_account,
+        &accounts.mint,
+        &accounts.token_program,
+        ctx.remaining_accounts,
+    )?;
+
+    emit_event!(ctx, event);
+    Ok(())
+}
@@ -0,0 +1,246 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{
+    emit_event, error::ErrorCode, events::GrantCreated, ClaimPermission, EmployeeAccount,
+    GrantCounter, GrantParams, Milestone, VestingAccount, VestingSchedule, ANCHOR_DISCRIMINATOR,
+    MAX_MILESTONES,
+};
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(grant_id: u64)]
+pub struct CreateEmployeeAccount<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub beneficiary: SystemAccount<'info>,
+    #[account(
+    mut,
+    has_one = owner,
+    has_one = treasury_token_account,
+    constraint = vesting_account.is_single_owner() @ ErrorCode::MultisigRequired,
+    constraint = !vesting_account.paused @ ErrorCode::VestingPaused,
+  )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+    init_if_needed,
+    payer = owner,
+    space = ANCHOR_DISCRIMINATOR + GrantCounter::INIT_SPACE,
+    seeds = [b"grant_counter", vesting_account.key().as_ref(), beneficiary.key().as_ref()],
+    bump
+  )]
+    pub grant_counter: Account<'info, GrantCounter>,
+
+    #[account(
+    init,
+    payer = owner,
+    space = ANCHOR_DISCRIMINATOR + EmployeeAccount::INIT_SPACE,
+    seeds = [b"employee_vesting", beneficiary.key().as_ref(), vesting_account.key().as_ref(), grant_id.to_le_bytes().as_ref()],
+    bump
+  )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn validate_schedule(
+    start_time: i64,
+    end_time: i64,
+    cliff_time: i64,
+    total_amount: u64,
+    schedule: VestingSchedule,
+) -> Result<()> {
+    require!(end_time > start_time, ErrorCode::InvalidVestingPeriod);
+    require!(cliff_time >= start_time, ErrorCode::CliffBeforeStart);
+    require!(cliff_time <= end_time, ErrorCode::CliffAfterEnd);
+    require!(total_amount > 0, ErrorCode::InvalidAmount);
+    if let VestingSchedule::Stepped { period } = schedule {
+        let duration = end_time
+            .checked_sub(start_time)
+            .ok_or(ErrorCode::InvalidVestingPeriod)?;
+        require!(
+            period > 0 && period <= duration,
+            ErrorCode::InvalidStepPeriod
+        );
+    }
+    Ok(())
+}
+
+/// Turns the tranche amounts of a milestone grant into unattested milestones.
+/// Time-based grants take no tranches.
+pub fn build_milestones(
+    schedule: VestingSchedule,
+    total_amount: u64,
+    amounts: Vec<u64>,
+) -> Result<Vec<Milestone>> {
+    if schedule != VestingSchedule::Milestone {
+        require!(amounts.is_empty(), ErrorCode::InvalidMilestones);
+        return Ok(Vec::new());
+    }
+
+    require!(
+        !amounts.is_empty() && amounts.len() <= MAX_MILESTONES,
+        ErrorCode::InvalidMilestones
+    );
+    require!(
+        amounts.iter().all(|amount| *amount > 0),
+        ErrorCode::InvalidMilestones
+    );
+    let tranche_total = amounts
+        .iter()
+        .try_fold(0u64, |total, amount| total.checked_add(*amount))
+        .ok_or(ErrorCode::CalculationOverflow)?;
+    require_eq!(tranche_total, total_amount, ErrorCode::InvalidMilestones);
+
+    Ok(amounts
+        .into_iter()
+        .map(|amount| Milestone {
+            amount,
+            attested_at: None,
+        })
+        .collect())
+}
+
+/// Reserves `amount` of the treasury for a new grant.
+pub fn commit_treasury_tokens(
+    vesting_account: &mut VestingAccount,
+    treasury_balance: u64,
+    amount: u64,
+) -> Result<()> {
+    let committed_amount = vesting_account
+        .committed_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::CalculationOverflow)?;
+    require!(
+        committed_amount <= treasury_balance,
+        ErrorCode::InsufficientTreasuryBalance
+    );
+    vesting_account.committed_amount = committed_amount;
+    Ok(())
+}
+
+/// Loads a beneficiary's grant counter, filling in a freshly created one.
+pub fn load_grant_counter(
+    grant_counter: &mut GrantCounter,
+    vesting_account: Pubkey,
+    beneficiary: Pubkey,
+    bump: u8,
+) {
+    if grant_counter.beneficiary == Pubkey::default() {
+        grant_counter.beneficiary = beneficiary;
+        grant_counter.vesting_account = vesting_account;
+        grant_counter.bump = bump;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn save_employee_account(
+    ctx: Context<CreateEmployeeAccount>,
+    grant_id: u64,
+    start_time: i64,
+    end_time: i64,
+    cliff_time: i64,
+    total_amount: u64,
+    schedule: VestingSchedule,
+    milestones: Vec<u64>,
+) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    load_grant_counter(
+        &mut accounts.grant_counter,
+        accounts.vesting_account.key(),
+        accounts.beneficiary.key(),
+        ctx.bumps.grant_counter,
+    );
+
+    let employee_account = open_grant(
+        &mut accounts.vesting_account,
+        accounts.treasury_token_account.amount,
+        &mut accounts.grant_counter,
+        accounts.beneficiary.key(),
+        accounts.owner.key(),
+        GrantParams {
+            grant_id,
+            start_time,
+            end_time,
+            cliff_time,
+            total_amount,
+            schedule,
+            milestones,
+        },
+        ctx.bumps.employee_account,
+    )?;
+    accounts.employee_account.set_inner(employee_account);
+
+    emit_event!(
+        ctx,
+        grant_created(
+            ctx.accounts.employee_account.key(),
+            &ctx.accounts.employee_account
+        )
+    );
+    Ok(())
+}
+
+/// Validates `grant`, takes its id from the beneficiary's counter and reserves its
+/// tokens in the treasury, returning the data of the new grant account.
+pub fn open_grant(
+    vesting_account: &mut Account<VestingAccount>,
+    treasury_balance: u64,
+    grant_counter: &mut GrantCounter,
+    beneficiary: Pubkey,
+    payer: Pubkey,
+    grant: GrantParams,
+    bump: u8,
+) -> Result<EmployeeAccount> {
+    validate_schedule(
+        grant.start_time,
+        grant.end_time,
+        grant.cliff_time,
+        grant.total_amount,
+        grant.schedule,
+    )?;
+    let milestones = build_milestones(grant.schedule, grant.total_amount, grant.milestones)?;
+
+    grant_counter.next_grant(grant.grant_id)?;
+
+    vesting_account.active_grants = vesting_account
+        .active_grants
+        .checked_add(1)
+        .ok_or(ErrorCode::CalculationOverflow)?;
+
+    commit_treasury_tokens(vesting_account, treasury_balance, grant.total_amount)?;
+
+    Ok(EmployeeAccount {
+        beneficiary,
+        start_time: grant.start_time,
+        end_time: grant.end_time,
+        cliff_time: grant.cliff_time,
+        vesting_account: vesting_account.key(),
+        grant_id: grant.grant_id,
+        total_amount: grant.total_amount,
+        total_withdrawn: 0,
+        total_transfer_fees: 0,
+        schedule: grant.schedule,
+        milestones,
+        payer,
+        claim_permission: ClaimPermission::BeneficiaryOnly,
+        revoked_at: None,
+        bump,
+    })
+}
+
+pub fn grant_created(employee_key: Pubkey, employee_account: &EmployeeAccount) -> GrantCreated {
+    GrantCreated {
+        vesting_account: employee_account.vesting_account,
+        employee_account: employee_key,
+        beneficiary: employee_account.beneficiary,
+        grant_id: employee_account.grant_id,
+        total_amount: employee_account.total_amount,
+        start_time: employee_account.start_time,
+        end_time: employee_account.end_time,
+        cliff_time: employee_account.cliff_time,
+    }
+}
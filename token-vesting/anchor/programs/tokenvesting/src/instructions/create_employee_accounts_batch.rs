@@ -0,0 +1,257 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{
+    emit_event, error::ErrorCode, EmployeeAccount, GrantCounter, GrantParams, VestingAccount,
+};
+
+use super::{
+    build_milestones, create_program_account, grant_created, open_grant, validate_schedule,
+};
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CreateEmployeeAccountsBatch<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = treasury_token_account,
+        constraint = vesting_account.is_single_owner() @ ErrorCode::MultisigRequired,
+        constraint = !vesting_account.paused @ ErrorCode::VestingPaused,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// One grant of a batch.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GrantEntry {
+    pub beneficiary: Pubkey,
+    pub grant: GrantParams,
+}
+
+/// Remaining accounts hold a `[grant_counter, employee_account]` pair per entry, in
+/// entry order. Every entry is checked before anything is written: each rejected
+/// entry is logged with its index and the first rejection fails the whole batch.
+pub fn save_employee_accounts_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateEmployeeAccountsBatch<'info>>,
+    entries: Vec<GrantEntry>,
+) -> Result<()> {
+    require!(!entries.is_empty(), ErrorCode::InvalidBatch);
+    require_eq!(
+        ctx.remaining_accounts.len(),
+        entries.len() * 2,
+        ErrorCode::InvalidBatch
+    );
+
+    let vesting_key = ctx.accounts.vesting_account.key();
+    let mut next_grant_ids = Vec::new();
+    let mut first_error = None;
+    for (index, (entry, accounts)) in entries
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(2))
+        .enumerate()
+    {
+        if let Err(error) = check_entry(
+            vesting_key,
+            entry,
+            &accounts[0],
+            &accounts[1],
+            &mut next_grant_ids,
+        ) {
+            msg!(
+                "Batch entry {} for {} rejected: {}",
+                index,
+                entry.beneficiary,
+                error
+            );
+            first_error.get_or_insert(error);
+        }
+    }
+    if let Some(error) = first_error {
+        return Err(error);
+    }
+
+    let treasury_balance = ctx.accounts.treasury_token_account.amount;
+    let batch_amount = entries
+        .iter()
+        .try_fold(0u64, |total, entry| {
+            total.checked_add(entry.grant.total_amount)
+        })
+        .ok_or(ErrorCode::CalculationOverflow)?;
+    let committed_amount = ctx
+        .accounts
+        .vesting_account
+        .committed_amount
+        .checked_add(batch_amount)
+        .ok_or(ErrorCode::CalculationOverflow)?;
+    require!(
+        committed_amount <= treasury_balance,
+        ErrorCode::InsufficientTreasuryBalance
+    );
+
+    let owner = ctx.accounts.owner.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    for (entry, accounts) in entries.into_iter().zip(ctx.remaining_accounts.chunks(2)) {
+        let (grant_counter_info, employee_info) = (&accounts[0], &accounts[1]);
+        let beneficiary = entry.beneficiary;
+
+        let mut grant_counter = match load_grant_counter_account(grant_counter_info)? {
+            Some(grant_counter) => grant_counter,
+            None => {
+                let (_, bump) = grant_counter_address(vesting_key, beneficiary);
+                create_program_account(
+                    &owner,
+                    grant_counter_info,
+                    GrantCounter::INIT_SPACE,
+                    &[
+                        b"grant_counter",
+                        vesting_key.as_ref(),
+                        beneficiary.as_ref(),
+                        &[bump],
+                    ],
+                    &system_program,
+                )?;
+                GrantCounter {
+                    beneficiary,
+                    vesting_account: vesting_key,
+                    grant_count: 0,
+                    bump,
+                }
+            }
+        };
+
+        let grant_id = entry.grant.grant_id;
+        let (employee_key, bump) = employee_account_address(vesting_key, beneficiary, grant_id);
+        let employee_account = open_grant(
+            &mut ctx.accounts.vesting_account,
+            treasury_balance,
+            &mut grant_counter,
+            beneficiary,
+            owner.key(),
+            entry.grant,
+            bump,
+        )?;
+        create_program_account(
+            &owner,
+            employee_info,
+            EmployeeAccount::INIT_SPACE,
+            &[
+                b"employee_vesting",
+                beneficiary.as_ref(),
+                vesting_key.as_ref(),
+                grant_id.to_le_bytes().as_ref(),
+                &[bump],
+            ],
+            &system_program,
+        )?;
+
+        grant_counter.try_serialize(&mut &mut grant_counter_info.try_borrow_mut_data()?[..])?;
+        employee_account.try_serialize(&mut &mut employee_info.try_borrow_mut_data()?[..])?;
+
+        emit_event!(ctx, grant_created(employee_key, &employee_account));
+    }
+    Ok(())
+}
+
+/// Checks an entry's terms and accounts against the grant counters as they will be
+/// once the earlier entries of the batch are written.
+fn check_entry(
+    vesting_account: Pubkey,
+    entry: &GrantEntry,
+    grant_counter: &AccountInfo,
+    employee_account: &AccountInfo,
+    next_grant_ids: &mut Vec<(Pubkey, u64)>,
+) -> Result<()> {
+    let grant = &entry.grant;
+    validate_schedule(
+        grant.start_time,
+        grant.end_time,
+        grant.cliff_time,
+        grant.total_amount,
+        grant.schedule,
+    )?;
+    build_milestones(grant.schedule, grant.total_amount, grant.milestones.clone())?;
+
+    let (grant_counter_key, _) = grant_counter_address(vesting_account, entry.beneficiary);
+    require_keys_eq!(
+        grant_counter.key(),
+        grant_counter_key,
+        ErrorCode::InvalidGrantAccount
+    );
+    let (employee_key, _) =
+        employee_account_address(vesting_account, entry.beneficiary, grant.grant_id);
+    require_keys_eq!(
+        employee_account.key(),
+        employee_key,
+        ErrorCode::InvalidGrantAccount
+    );
+    // A system-owned address may hold lamports sent ahead of time; `create_program_account`
+    // takes it over all the same.
+    require!(
+        employee_account.owner == &System::id() && employee_account.data_is_empty(),
+        ErrorCode::GrantAlreadyExists
+    );
+
+    let position = match next_grant_ids
+        .iter()
+        .position(|(beneficiary, _)| *beneficiary == entry.beneficiary)
+    {
+        Some(position) => position,
+        None => {
+            let grant_count = load_grant_counter_account(grant_counter)?
+                .map_or(0, |grant_counter| grant_counter.grant_count);
+            next_grant_ids.push((entry.beneficiary, grant_count));
+            next_grant_ids.len() - 1
+        }
+    };
+    let next_grant_id = &mut next_grant_ids[position].1;
+    let expected_grant_id = *next_grant_id;
+    *next_grant_id += 1;
+    require_eq!(grant.grant_id, expected_grant_id, ErrorCode::InvalidGrantId);
+    Ok(())
+}
+
+/// Reads a grant counter, or `None` if it has not been created yet, even if the address
+/// already holds lamports.
+fn load_grant_counter_account(account: &AccountInfo) -> Result<Option<GrantCounter>> {
+    if account.owner == &System::id() && account.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*account.owner, crate::ID, ErrorCode::InvalidGrantAccount);
+    let data = account.try_borrow_data()?;
+    GrantCounter::try_deserialize(&mut &data[..]).map(Some)
+}
+
+fn grant_counter_address(vesting_account: Pubkey, beneficiary: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"grant_counter",
+            vesting_account.as_ref(),
+            beneficiary.as_ref(),
+        ],
+        &crate::ID,
+    )
+}
+
+fn employee_account_address(
+    vesting_account: Pubkey,
+    beneficiary: Pubkey,
+    grant_id: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"employee_vesting",
+            beneficiary.as_ref(),
+            vesting_account.as_ref(),
+            grant_id.to_le_bytes().as_ref(),
+        ],
+        &crate::ID,
+    )
+}
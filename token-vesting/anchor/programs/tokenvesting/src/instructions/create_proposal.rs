@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    emit_event,
+    error::ErrorCode,
+    events::{ProposalApproved, ProposalCancelled, ProposalCreated},
+    Proposal, ProposalAction, VestingAccount, ANCHOR_DISCRIMINATOR,
+};
+
+/// An admin proposes an action; their own approval is recorded right away.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = !vesting_account.is_single_owner() @ ErrorCode::NotMultisig,
+        constraint = vesting_account.admins.contains(&proposer.key()) @ ErrorCode::NotAdmin,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = ANCHOR_DISCRIMINATOR + Proposal::INIT_SPACE,
+        seeds = [b"proposal", vesting_account.key().as_ref(), vesting_account.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = vesting_account.admins.contains(&admin.key()) @ ErrorCode::NotAdmin,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", vesting_account.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = vesting_account,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+/// The proposer withdraws a proposal that has not been executed and gets its rent back.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(mut)]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", vesting_account.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = vesting_account,
+        has_one = proposer,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+pub fn save_proposal(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+    let vesting_account = &mut ctx.accounts.vesting_account;
+    let proposal_id = vesting_account.proposal_count;
+    vesting_account.proposal_count = proposal_id
+        .checked_add(1)
+        .ok_or(ErrorCode::CalculationOverflow)?;
+    vesting_account.open_proposals = vesting_account
+        .open_proposals
+        .checked_add(1)
+        .ok_or(ErrorCode::CalculationOverflow)?;
+
+    ctx.accounts.proposal.set_inner(Proposal {
+        vesting_account: vesting_account.key(),
+        proposal_id,
+        proposer: ctx.accounts.proposer.key(),
+        action,
+        approvals: vec![ctx.accounts.proposer.key()],
+        bump: ctx.bumps.proposal,
+    });
+
+    emit_event!(
+        ctx,
+        ProposalCreated {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            proposal: ctx.accounts.proposal.key(),
+            proposal_id,
+            proposer: ctx.accounts.proposer.key(),
+        }
+    );
+    Ok(())
+}
+
+pub fn record_approval(ctx: Context<ApproveProposal>) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    let proposal = &mut ctx.accounts.proposal;
+    require!(
+        !proposal.approvals.contains(&admin),
+        ErrorCode::AlreadyApproved
+    );
+    // Approvals of removed admins no longer count; drop them to make room.
+    let vesting_account = &ctx.accounts.vesting_account;
+    proposal
+        .approvals
+        .retain(|approver| vesting_account.admins.contains(approver));
+    proposal.approvals.push(admin);
+
+    emit_event!(
+        ctx,
+        ProposalApproved {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            proposal: ctx.accounts.proposal.key(),
+            admin,
+            approvals: ctx.accounts.proposal.approvals.len() as u8,
+        }
+    );
+    Ok(())
+}
+
+pub fn discard_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+    proposal_closed(&mut ctx.accounts.vesting_account)?;
+    emit_event!(
+        ctx,
+        ProposalCancelled {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            proposal: ctx.accounts.proposal.key(),
+            proposal_id: ctx.accounts.proposal.proposal_id,
+        }
+    );
+    Ok(())
+}
+
+/// Counts out a proposal that is executed or cancelled, and so closed.
+pub fn proposal_closed(vesting_account: &mut VestingAccount) -> Result<()> {
+    vesting_account.open_proposals = vesting_account
+        .open_proposals
+        .checked_sub(1)
+        .ok_or(ErrorCode::CalculationOverflow)?;
+    Ok(())
+}
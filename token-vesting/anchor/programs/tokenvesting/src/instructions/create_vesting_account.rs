@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    emit_event, events::VestingAccountCreated, extensions::validate_mint, VestingAccount,
+    ANCHOR_DISCRIMINATOR, VESTING_VERSION,
+};
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(company_name: String)]
+pub struct CreateVestingAccount<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+    init,
+    payer = signer,
+    space = ANCHOR_DISCRIMINATOR + VestingAccount::INIT_SPACE,
+    seeds = [b"vesting", signer.key().as_ref(), company_name.as_bytes()],
+    bump
+  )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+      init,
+      payer = signer,
+      token::mint = mint,
+      token::authority = treasury_token_account,
+      seeds = [b"vesting_treasury", signer.key().as_ref(), company_name.as_bytes()],
+      bump
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn save_vesting_account(
+    ctx: Context<CreateVestingAccount>,
+    company_name: String,
+) -> Result<()> {
+    validate_mint(&ctx.accounts.mint)?;
+
+    *ctx.accounts.vesting_account = VestingAccount {
+        owner: ctx.accounts.signer.key(),
+        pending_owner: None,
+        creator: ctx.accounts.signer.key(),
+        mint: ctx.accounts.mint.key(),
+        treasury_token_account: ctx.accounts.treasury_token_account.key(),
+        company_name,
+        committed_amount: 0,
+        total_deposited: 0,
+        active_grants: 0,
+        version: VESTING_VERSION,
+        legacy_vesting_account: None,
+        legacy_reserved: 0,
+        legacy_reserve_expires_at: 0,
+        admins: Vec::new(),
+        threshold: 0,
+        proposal_count: 0,
+        open_proposals: 0,
+        paused: false,
+        treasury_bump: ctx.bumps.treasury_token_account,
+        bump: ctx.bumps.vesting_account,
+    };
+
+    emit_event!(
+        ctx,
+        VestingAccountCreated {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            owner: ctx.accounts.vesting_account.owner,
+            mint: ctx.accounts.vesting_account.mint,
+            company_name: ctx.accounts.vesting_account.company_name.clone(),
+        }
+    );
+    Ok(())
+}
@@ -0,0 +1,404 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    emit_event,
+    error::ErrorCode,
+    events::{OwnerTransferAccepted, ProposalExecuted},
+    EmployeeAccount, GrantCounter, Proposal, ProposalAction, VestingAccount, ANCHOR_DISCRIMINATOR,
+};
+
+use super::{
+    grant_created, load_grant_counter, open_grant, proposal_closed, record_attestation,
+    replace_admins, revoke_grant, withdraw_unallocated_tokens,
+};
+
+/// Opens the grant of an approved `CreateGrant` proposal. Anyone may execute it; the
+/// executor pays the rent of the new grant account.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ExecuteCreateGrant<'info> {
+    #[account(mut)]
+    pub executor: Signer<'info>,
+
+    pub beneficiary: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = treasury_token_account,
+        constraint = !vesting_account.paused @ ErrorCode::VestingPaused,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", vesting_account.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = vesting_account,
+        has_one = proposer,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        init_if_needed,
+        payer = executor,
+        space = ANCHOR_DISCRIMINATOR + GrantCounter::INIT_SPACE,
+        seeds = [b"grant_counter", vesting_account.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    pub grant_counter: Account<'info, GrantCounter>,
+
+    #[account(
+        init,
+        payer = executor,
+        space = ANCHOR_DISCRIMINATOR + EmployeeAccount::INIT_SPACE,
+        seeds = [b"employee_vesting", beneficiary.key().as_ref(), vesting_account.key().as_ref(), proposal.action.grant_id().to_le_bytes().as_ref()],
+        bump
+    )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ExecuteRevokeGrant<'info> {
+    #[account(
+        mut,
+        has_one = treasury_token_account,
+        has_one = mint,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"employee_vesting", employee_account.beneficiary.as_ref(), vesting_account.key().as_ref(), employee_account.grant_id.to_le_bytes().as_ref()],
+        bump = employee_account.bump,
+        has_one = vesting_account,
+    )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+
+    #[account(mut)]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", vesting_account.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = vesting_account,
+        has_one = proposer,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ExecuteAttestMilestone<'info> {
+    #[account(mut)]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"employee_vesting", employee_account.beneficiary.as_ref(), vesting_account.key().as_ref(), employee_account.grant_id.to_le_bytes().as_ref()],
+        bump = employee_account.bump,
+        has_one = vesting_account,
+    )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", vesting_account.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = vesting_account,
+        has_one = proposer,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ExecuteWithdrawUnallocated<'info> {
+    #[account(
+        mut,
+        has_one = treasury_token_account,
+        has_one = mint,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(mut)]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", vesting_account.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = vesting_account,
+        has_one = proposer,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ExecuteSetAdmins<'info> {
+    #[account(mut)]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", vesting_account.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = vesting_account,
+        has_one = proposer,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ExecuteSetOwner<'info> {
+    #[account(mut)]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", vesting_account.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = vesting_account,
+        has_one = proposer,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+/// Only proposals approved by `threshold` current admins can run, and only while the
+/// vesting account is still governed by its admins. The proposal is closed once it runs.
+fn require_approved(vesting_account: &mut VestingAccount, proposal: &Proposal) -> Result<()> {
+    require!(!vesting_account.is_single_owner(), ErrorCode::NotMultisig);
+    require!(
+        vesting_account.approval_count(&proposal.approvals) >= vesting_account.threshold as usize,
+        ErrorCode::NotEnoughApprovals
+    );
+    proposal_closed(vesting_account)
+}
+
+fn proposal_executed(proposal: &Account<Proposal>) -> ProposalExecuted {
+    ProposalExecuted {
+        vesting_account: proposal.vesting_account,
+        proposal: proposal.key(),
+        proposal_id: proposal.proposal_id,
+    }
+}
+
+pub fn open_proposed_grant(ctx: Context<ExecuteCreateGrant>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    require_approved(&mut accounts.vesting_account, &accounts.proposal)?;
+    let ProposalAction::CreateGrant { beneficiary, grant } = accounts.proposal.action.clone()
+    else {
+        return Err(ErrorCode::ProposalActionMismatch.into());
+    };
+    require_keys_eq!(
+        beneficiary,
+        accounts.beneficiary.key(),
+        ErrorCode::ProposalActionMismatch
+    );
+
+    load_grant_counter(
+        &mut accounts.grant_counter,
+        accounts.vesting_account.key(),
+        beneficiary,
+        ctx.bumps.grant_counter,
+    );
+    let employee_account = open_grant(
+        &mut accounts.vesting_account,
+        accounts.treasury_token_account.amount,
+        &mut accounts.grant_counter,
+        beneficiary,
+        accounts.executor.key(),
+        grant,
+        ctx.bumps.employee_account,
+    )?;
+    accounts.employee_account.set_inner(employee_account);
+
+    emit_event!(
+        ctx,
+        grant_created(
+            ctx.accounts.employee_account.key(),
+            &ctx.accounts.employee_account
+        )
+    );
+    emit_event!(ctx, proposal_executed(&ctx.accounts.proposal));
+    Ok(())
+}
+
+/// Remaining accounts are forwarded to the token program for transfer-hook mints.
+pub fn revoke_proposed_grant<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteRevokeGrant<'info>>,
+) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    require_approved(&mut accounts.vesting_account, &accounts.proposal)?;
+    require!(
+        accounts.proposal.action
+            == ProposalAction::RevokeGrant {
+                employee_account: accounts.employee_account.key(),
+                destination_token_account: accounts.destination_token_account.key(),
+            },
+        ErrorCode::ProposalActionMismatch
+    );
+
+    let event = revoke_grant(
+        &mut accounts.vesting_account,
+        &mut accounts.employee_account,
+        &accounts.treasury_token_account,
+        &accounts.destination_token_account,
+        &accounts.mint,
+        &accounts.token_program,
+        ctx.remaining_accounts,
+    )?;
+
+    emit_event!(ctx, event);
+    emit_event!(ctx, proposal_executed(&ctx.accounts.proposal));
+    Ok(())
+}
+
+pub fn attest_proposed_milestone(ctx: Context<ExecuteAttestMilestone>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    require_approved(&mut accounts.vesting_account, &accounts.proposal)?;
+    let ProposalAction::AttestMilestone {
+        employee_account,
+        milestone_index,
+    } = accounts.proposal.action
+    else {
+        return Err(ErrorCode::ProposalActionMismatch.into());
+    };
+    require_keys_eq!(
+        employee_account,
+        accounts.employee_account.key(),
+        ErrorCode::ProposalActionMismatch
+    );
+
+    let event = record_attestation(&mut accounts.employee_account, milestone_index)?;
+
+    emit_event!(ctx, event);
+    emit_event!(ctx, proposal_executed(&ctx.accounts.proposal));
+    Ok(())
+}
+
+/// Remaining accounts are forwarded to the token program for transfer-hook mints.
+pub fn withdraw_proposed_amount<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteWithdrawUnallocated<'info>>,
+) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    require_approved(&mut accounts.vesting_account, &accounts.proposal)?;
+    let ProposalAction::WithdrawUnallocated {
+        destination_token_account,
+        amount,
+    } = accounts.proposal.action
+    else {
+        return Err(ErrorCode::ProposalActionMismatch.into());
+    };
+    require_keys_eq!(
+        destination_token_account,
+        accounts.destination_token_account.key(),
+        ErrorCode::ProposalActionMismatch
+    );
+
+    let event = withdraw_unallocated_tokens(
+        &accounts.vesting_account,
+        &accounts.treasury_token_account,
+        &accounts.destination_token_account,
+        amount,
+        &accounts.mint,
+        &accounts.token_program,
+        ctx.remaining_accounts,
+    )?;
+
+    emit_event!(ctx, event);
+    emit_event!(ctx, proposal_executed(&ctx.accounts.proposal));
+    Ok(())
+}
+
+pub fn apply_proposed_admins(ctx: Context<ExecuteSetAdmins>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    require_approved(&mut accounts.vesting_account, &accounts.proposal)?;
+    let ProposalAction::SetAdmins { admins, threshold } = accounts.proposal.action.clone() else {
+        return Err(ErrorCode::ProposalActionMismatch.into());
+    };
+
+    let event = replace_admins(&mut accounts.vesting_account, admins, threshold)?;
+
+    emit_event!(ctx, event);
+    emit_event!(ctx, proposal_executed(&ctx.accounts.proposal));
+    Ok(())
+}
+
+pub fn apply_proposed_owner(ctx: Context<ExecuteSetOwner>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    require_approved(&mut accounts.vesting_account, &accounts.proposal)?;
+    let ProposalAction::SetOwner { new_owner } = accounts.proposal.action else {
+        return Err(ErrorCode::ProposalActionMismatch.into());
+    };
+
+    let vesting_account = &mut accounts.vesting_account;
+    let previous_owner = vesting_account.owner;
+    vesting_account.owner = new_owner;
+    vesting_account.pending_owner = None;
+
+    emit_event!(
+        ctx,
+        OwnerTransferAccepted {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            previous_owner,
+            new_owner,
+        }
+    );
+    emit_event!(ctx, proposal_executed(&ctx.accounts.proposal));
+    Ok(())
+}
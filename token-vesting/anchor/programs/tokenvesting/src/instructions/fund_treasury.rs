@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    emit_event, error::ErrorCode, events::TreasuryFunded, extensions::transfer_fee, VestingAccount,
+};
+
+use super::transfer_tokens;
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct FundTreasury<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = treasury_token_account,
+        has_one = mint,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(mut)]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn deposit_into_treasury<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FundTreasury<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    transfer_tokens(
+        &ctx.accounts.owner_token_account,
+        &ctx.accounts.treasury_token_account,
+        ctx.accounts.owner.to_account_info(),
+        amount,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+        &[],
+    )?;
+
+    // Transfer-fee mints withhold part of the deposit in the treasury account.
+    let deposited_amount = amount.saturating_sub(transfer_fee(&ctx.accounts.mint, amount)?);
+    let vesting_account = &mut ctx.accounts.vesting_account;
+    vesting_account.total_deposited = vesting_account
+        .total_deposited
+        .checked_add(deposited_amount)
+        .ok_or(ErrorCode::CalculationOverflow)?;
+
+    emit_event!(
+        ctx,
+        TreasuryFunded {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            funder: ctx.accounts.owner.key(),
+            amount: deposited_amount,
+            total_deposited: ctx.accounts.vesting_account.total_deposited,
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::EmployeeAccount;
+
+#[derive(Accounts)]
+pub struct GetVestingStatus<'info> {
+    pub employee_account: Account<'info, EmployeeAccount>,
+}
+
+/// Snapshot of a grant at a given time, as `claim_tokens` would see it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VestingStatus {
+    pub vested_amount: u64,
+    pub withdrawn_amount: u64,
+    pub claimable_amount: u64,
+    /// Next time more of the grant vests; `None` once everything has vested, when nothing
+    /// more vests before the grant's revocation, or while the remaining milestones are
+    /// unattested. Queried for a time before the revocation, it still reports the unlocks
+    /// that happened before it.
+    pub next_unlock_time: Option<i64>,
+}
+
+/// Read-only; meant to be called through `simulateTransaction`, which returns the
+/// status as the instruction's return data.
+pub fn vesting_status_at(ctx: Context<GetVestingStatus>, timestamp: i64) -> Result<VestingStatus> {
+    let employee_account = &ctx.accounts.employee_account;
+    let vested_amount = employee_account.vested_amount(timestamp)?;
+
+    Ok(VestingStatus {
+        vested_amount,
+        withdrawn_amount: employee_account.total_withdrawn,
+        claimable_amount: vested_amount.saturating_sub(employee_account.total_withdrawn),
+        next_unlock_time: employee_account.next_unlock_time(timestamp)?,
+    })
+}
@@ -0,0 +1,279 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    error::ErrorCode, extensions::validate_mint, ClaimPermission, EmployeeAccount, GrantCounter,
+    LegacyEmployeeAccount, LegacyVestingAccount, VestingAccount, VestingSchedule,
+    ANCHOR_DISCRIMINATOR, LEGACY_MIGRATION_WINDOW, VESTING_VERSION,
+};
+
+use super::{
+    close_program_account, close_signed, commit_treasury_tokens, load_grant_counter,
+    transfer_signed,
+};
+
+/// Moves a legacy vesting account, derived from `[company_name]`, and its treasury to the
+/// namespaced derivation. Its grants follow one by one through `migrate_employee_account`;
+/// until they have, the whole legacy treasury stays reserved for them.
+#[derive(Accounts)]
+#[instruction(company_name: String)]
+pub struct MigrateVestingAccount<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: Legacy layout, loaded and checked with `LegacyVestingAccount::load`.
+    #[account(
+        mut,
+        seeds = [company_name.as_ref()],
+        bump,
+    )]
+    pub legacy_vesting_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting_treasury", company_name.as_bytes()],
+        bump,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub legacy_treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + VestingAccount::INIT_SPACE,
+        seeds = [b"vesting", owner.key().as_ref(), company_name.as_bytes()],
+        bump
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    /// Receives the transfer fees still withheld in the legacy treasury.
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = owner,
+        token::mint = mint,
+        token::authority = treasury_token_account,
+        seeds = [b"vesting_treasury", owner.key().as_ref(), company_name.as_bytes()],
+        bump
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Re-homes a grant of a migrated legacy vesting account, derived from
+/// `[b"employee_vesting", beneficiary, legacy_vesting_account]`, under the new vesting account
+/// with the beneficiary's next grant id. Open to anyone, so that the owner cannot hold a
+/// legacy grant back; whoever signs pays the rent of the new accounts.
+#[derive(Accounts)]
+pub struct MigrateEmployeeAccount<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Receives the rent of the legacy grant, which the owner paid.
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = treasury_token_account,
+        constraint = vesting_account.legacy_vesting_account.is_some() @ ErrorCode::NotMigrationSource,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub beneficiary: SystemAccount<'info>,
+
+    /// CHECK: Legacy layout, loaded and checked with `LegacyEmployeeAccount::load`.
+    #[account(
+        mut,
+        seeds = [b"employee_vesting", beneficiary.key().as_ref(), vesting_account.legacy_vesting_account.unwrap_or_default().as_ref()],
+        bump,
+    )]
+    pub legacy_employee_account: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + GrantCounter::INIT_SPACE,
+        seeds = [b"grant_counter", vesting_account.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    pub grant_counter: Account<'info, GrantCounter>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + EmployeeAccount::INIT_SPACE,
+        seeds = [b"employee_vesting", beneficiary.key().as_ref(), vesting_account.key().as_ref(), grant_counter.grant_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub new_employee_account: Account<'info, EmployeeAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Frees what is left of the legacy reserve once `LEGACY_MIGRATION_WINDOW` has passed, for
+/// legacy treasury tokens that no grant claimed. Grants migrated later commit against the
+/// treasury like new grants.
+#[derive(Accounts)]
+pub struct ReleaseLegacyReserve<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub vesting_account: Account<'info, VestingAccount>,
+}
+
+/// Remaining accounts are forwarded to the token program for transfer-hook mints.
+pub fn migrate_legacy_vesting_account<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MigrateVestingAccount<'info>>,
+    company_name: String,
+) -> Result<()> {
+    validate_mint(&ctx.accounts.mint)?;
+    let legacy = LegacyVestingAccount::load(&ctx.accounts.legacy_vesting_account)?;
+    require_keys_eq!(
+        legacy.owner,
+        ctx.accounts.owner.key(),
+        ErrorCode::Unauthorized
+    );
+    require_keys_eq!(
+        legacy.treasury_token_account,
+        ctx.accounts.legacy_treasury_token_account.key(),
+        ErrorCode::NotMigrationSource
+    );
+
+    let legacy_balance = ctx.accounts.legacy_treasury_token_account.amount;
+    if legacy_balance > 0 {
+        transfer_signed(
+            &ctx.accounts.legacy_treasury_token_account,
+            &ctx.accounts.treasury_token_account,
+            legacy_balance,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+            &legacy.treasury_seeds(),
+        )?;
+    }
+    close_signed(
+        &ctx.accounts.legacy_treasury_token_account,
+        &ctx.accounts.mint,
+        ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.token_program,
+        &legacy.treasury_seeds(),
+    )?;
+    ctx.accounts.treasury_token_account.reload()?;
+
+    // Legacy accounts kept no commitments, so everything they held is reserved until their
+    // grants have migrated.
+    let legacy_reserved = ctx.accounts.treasury_token_account.amount;
+    *ctx.accounts.vesting_account = VestingAccount {
+        owner: legacy.owner,
+        pending_owner: None,
+        creator: ctx.accounts.owner.key(),
+        mint: legacy.mint,
+        treasury_token_account: ctx.accounts.treasury_token_account.key(),
+        company_name,
+        committed_amount: legacy_reserved,
+        total_deposited: legacy_reserved,
+        active_grants: 0,
+        version: VESTING_VERSION,
+        legacy_vesting_account: Some(ctx.accounts.legacy_vesting_account.key()),
+        legacy_reserved,
+        legacy_reserve_expires_at: Clock::get()?
+            .unix_timestamp
+            .checked_add(LEGACY_MIGRATION_WINDOW)
+            .ok_or(ErrorCode::CalculationOverflow)?,
+        admins: Vec::new(),
+        threshold: 0,
+        proposal_count: 0,
+        open_proposals: 0,
+        paused: false,
+        treasury_bump: ctx.bumps.treasury_token_account,
+        bump: ctx.bumps.vesting_account,
+    };
+
+    close_program_account(
+        &ctx.accounts.legacy_vesting_account,
+        &ctx.accounts.owner.to_account_info(),
+    )
+}
+
+pub fn migrate_legacy_grant(ctx: Context<MigrateEmployeeAccount>) -> Result<()> {
+    let legacy = LegacyEmployeeAccount::load(&ctx.accounts.legacy_employee_account)?;
+    let unclaimed = legacy
+        .total_amount
+        .checked_sub(legacy.total_withdrawn)
+        .ok_or(ErrorCode::CalculationOverflow)?;
+
+    let grant_counter = &mut ctx.accounts.grant_counter;
+    load_grant_counter(
+        grant_counter,
+        ctx.accounts.vesting_account.key(),
+        ctx.accounts.beneficiary.key(),
+        ctx.bumps.grant_counter,
+    );
+    let grant_id = grant_counter.grant_count;
+    grant_counter.grant_count = grant_id
+        .checked_add(1)
+        .ok_or(ErrorCode::CalculationOverflow)?;
+
+    // The grant's remainder moves out of the legacy reserve; past what the reserve still
+    // holds, it is committed like a new grant.
+    let vesting_account = &mut ctx.accounts.vesting_account;
+    let from_reserve = unclaimed.min(vesting_account.legacy_reserved);
+    vesting_account.legacy_reserved -= from_reserve;
+    commit_treasury_tokens(
+        vesting_account,
+        ctx.accounts.treasury_token_account.amount,
+        unclaimed - from_reserve,
+    )?;
+    vesting_account.active_grants = vesting_account
+        .active_grants
+        .checked_add(1)
+        .ok_or(ErrorCode::CalculationOverflow)?;
+
+    ctx.accounts
+        .new_employee_account
+        .set_inner(EmployeeAccount {
+            beneficiary: legacy.beneficiary,
+            start_time: legacy.start_time,
+            end_time: legacy.end_time,
+            cliff_time: legacy.cliff_time,
+            vesting_account: ctx.accounts.vesting_account.key(),
+            grant_id,
+            total_amount: legacy.total_amount,
+            total_withdrawn: legacy.total_withdrawn,
+            total_transfer_fees: 0,
+            schedule: VestingSchedule::Linear,
+            milestones: Vec::new(),
+            payer: ctx.accounts.payer.key(),
+            claim_permission: ClaimPermission::BeneficiaryOnly,
+            revoked_at: None,
+            bump: ctx.bumps.new_employee_account,
+        });
+
+    close_program_account(
+        &ctx.accounts.legacy_employee_account,
+        &ctx.accounts.owner.to_account_info(),
+    )
+}
+
+pub fn release_unclaimed_legacy_reserve(ctx: Context<ReleaseLegacyReserve>) -> Result<()> {
+    let vesting_account = &mut ctx.accounts.vesting_account;
+    require!(
+        Clock::get()?.unix_timestamp >= vesting_account.legacy_reserve_expires_at,
+        ErrorCode::LegacyGrantsPending
+    );
+    vesting_account.committed_amount = vesting_account
+        .committed_amount
+        .checked_sub(vesting_account.legacy_reserved)
+        .ok_or(ErrorCode::CalculationOverflow)?;
+    vesting_account.legacy_reserved = 0;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+pub mod attest_milestone;
+pub use attest_milestone::*;
+
+pub mod claim_all;
+pub use claim_all::*;
+
+pub mod claim_tokens;
+pub use claim_tokens::*;
+
+pub mod close_employee_account;
+pub use close_employee_account::*;
+
+pub mod close_vesting_account;
+pub use close_vesting_account::*;
+
+pub mod crank_claim;
+pub use crank_claim::*;
+
+pub mod create_employee_account;
+pub use create_employee_account::*;
+
+pub mod create_employee_accounts_batch;
+pub use create_employee_accounts_batch::*;
+
+pub mod create_proposal;
+pub use create_proposal::*;
+
+pub mod create_vesting_account;
+pub use create_vesting_account::*;
+
+pub mod execute_proposal;
+pub use execute_proposal::*;
+
+pub mod fund_treasury;
+pub use fund_treasury::*;
+
+pub mod get_vesting_status;
+pub use get_vesting_status::*;
+
+pub mod migrate_vesting_account;
+pub use migrate_vesting_account::*;
+
+pub mod pause_vesting;
+pub use pause_vesting::*;
+
+pub mod revoke_employee_vesting;
+pub use revoke_employee_vesting::*;
+
+pub mod set_admins;
+pub use set_admins::*;
+
+pub mod shared;
+pub use shared::*;
+
+pub mod sol_vesting;
+pub use sol_vesting::*;
+
+pub mod transfer_beneficiary;
+pub use transfer_beneficiary::*;
+
+pub mod transfer_ownership;
+pub use transfer_ownership::*;
+
+pub mod withdraw_unallocated;
+pub use withdraw_unallocated::*;
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::{emit_event, events::PauseUpdated, VestingAccount};
+
+/// Emergency brake for claims and new grants. The owner holds it even when grants
+/// are governed by an admin set, since pausing moves no tokens.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub vesting_account: Account<'info, VestingAccount>,
+}
+
+pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    ctx.accounts.vesting_account.paused = paused;
+
+    emit_event!(
+        ctx,
+        PauseUpdated {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            paused,
+        }
+    );
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{emit_event, error::ErrorCode, events::GrantRevoked, EmployeeAccount, VestingAccount};
+
+use super::transfer_from_treasury;
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RevokeEmployeeVesting<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = treasury_token_account,
+        has_one = mint,
+        constraint = vesting_account.is_single_owner() @ ErrorCode::MultisigRequired,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"employee_vesting", employee_account.beneficiary.as_ref(), vesting_account.key().as_ref(), employee_account.grant_id.to_le_bytes().as_ref()],
+        bump = employee_account.bump,
+        has_one = vesting_account,
+    )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+
+    #[account(mut)]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Owner-chosen account that receives the unvested remainder.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Remaining accounts are forwarded to the token program for transfer-hook mints.
+pub fn revoke_vesting<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RevokeEmployeeVesting<'info>>,
+) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let event = revoke_grant(
+        &mut accounts.vesting_account,
+        &mut accounts.employee_account,
+        &accounts.treasury_token_account,
+        &accounts.destination_token_account,
+        &accounts.mint,
+        &accounts.token_program,
+        ctx.remaining_accounts,
+    )?;
+
+    emit_event!(ctx, event);
+    Ok(())
+}
+
+/// Stops the grant from vesting further and returns its unvested remainder to
+/// `destination_token_account`.
+pub fn revoke_grant<'info>(
+    vesting_account: &mut Account<'info, VestingAccount>,
+    employee_account: &mut Account<'info, EmployeeAccount>,
+    treasury_token_account: &InterfaceAccount<'info, TokenAccount>,
+    destination_token_account: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    additional_accounts: &[AccountInfo<'info>],
+) -> Result<GrantRevoked> {
+    let now = Clock::get()?.unix_timestamp;
+    let unvested_amount = stop_vesting(employee_account, vesting_account, now)?;
+
+    if unvested_amount > 0 {
+        transfer_from_treasury(
+            vesting_account,
+            treasury_token_account,
+            destination_token_account,
+            unvested_amount,
+            mint,
+            token_program,
+            additional_accounts,
+        )?;
+    }
+
+    Ok(GrantRevoked {
+        vesting_account: vesting_account.key(),
+        employee_account: employee_account.key(),
+        beneficiary: employee_account.beneficiary,
+        revoked_at: now,
+        returned_amount: unvested_amount,
+    })
+}
+
+/// Freezes the grant at `now` and releases its unvested remainder from the treasury
+/// commitment, returning that remainder.
+pub fn stop_vesting(
+    employee_account: &mut EmployeeAccount,
+    vesting_account: &mut VestingAccount,
+    now: i64,
+) -> Result<u64> {
+    if employee_account.revoked_at.is_some() {
+        return Err(ErrorCode::AlreadyRevoked.into());
+    }
+
+    let vested_amount = employee_account.vested_amount(now)?;
+    let unvested_amount = employee_account.total_amount.saturating_sub(vested_amount);
+
+    employee_account.revoked_at = Some(now);
+
+    vesting_account.committed_amount = vesting_account
+        .committed_amount
+        .saturating_sub(unvested_amount);
+    Ok(unvested_amount)
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::{emit_event, error::ErrorCode, events::AdminsUpdated, VestingAccount, MAX_ADMINS};
+
+/// Hands a single-owner vesting account over to an M-of-N admin set. Once set, the
+/// admins change it through `ProposalAction::SetAdmins` proposals. Proposals only
+/// execute against token treasuries, so native SOL accounts stay with their owner.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetAdmins<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        constraint = vesting_account.is_single_owner() @ ErrorCode::MultisigRequired,
+        constraint = !vesting_account.is_native_sol() @ ErrorCode::NativeSolUnsupported,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+}
+
+pub fn save_admins(ctx: Context<SetAdmins>, admins: Vec<Pubkey>, threshold: u8) -> Result<()> {
+    require!(threshold > 0, ErrorCode::InvalidAdminSet);
+    let event = replace_admins(&mut ctx.accounts.vesting_account, admins, threshold)?;
+
+    emit_event!(ctx, event);
+    Ok(())
+}
+
+/// Validates and stores a new admin set. An empty set with a zero threshold puts
+/// the vesting account back in single-owner mode.
+pub fn replace_admins(
+    vesting_account: &mut Account<VestingAccount>,
+    admins: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<AdminsUpdated> {
+    require!(admins.len() <= MAX_ADMINS, ErrorCode::InvalidAdminSet);
+    require!(
+        threshold as usize <= admins.len() && (threshold > 0 || admins.is_empty()),
+        ErrorCode::InvalidAdminSet
+    );
+    let mut unique_admins = admins.clone();
+    unique_admins.sort();
+    unique_admins.dedup();
+    require_eq!(
+        unique_admins.len(),
+        admins.len(),
+        ErrorCode::InvalidAdminSet
+    );
+
+    vesting_account.admins = admins;
+    vesting_account.threshold = threshold;
+
+    Ok(AdminsUpdated {
+        vesting_account: vesting_account.key(),
+        admins: vesting_account.admins.clone(),
+        threshold,
+    })
+}
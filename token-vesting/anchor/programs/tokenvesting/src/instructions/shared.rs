@@ -0,0 +1,241 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{
+        allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+    },
+};
+use anchor_spl::{
+    token_2022::spl_token_2022::onchain::invoke_transfer_checked,
+    token_interface::{
+        close_account, harvest_withheld_tokens_to_mint, CloseAccount, HarvestWithheldTokensToMint,
+        Mint, TokenAccount, TokenInterface,
+    },
+};
+
+use crate::{
+    error::ErrorCode, extensions::withheld_transfer_fees, GrantCounter, VestingAccount,
+    ANCHOR_DISCRIMINATOR,
+};
+
+pub fn transfer_from_treasury<'info>(
+    vesting_account: &Account<'info, VestingAccount>,
+    treasury_token_account: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    amount: u64,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    additional_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    transfer_signed(
+        treasury_token_account,
+        to,
+        amount,
+        mint,
+        token_program,
+        additional_accounts,
+        &vesting_account.treasury_seeds(),
+    )
+}
+
+pub fn close_treasury<'info>(
+    vesting_account: &Account<'info, VestingAccount>,
+    treasury_token_account: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    destination: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    close_signed(
+        treasury_token_account,
+        mint,
+        destination,
+        token_program,
+        &vesting_account.treasury_seeds(),
+    )
+}
+
+/// Transfers with `transfer_checked`, forwarding `additional_accounts` so Token-2022
+/// transfer hooks can resolve their extra accounts.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_tokens<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    authority: AccountInfo<'info>,
+    amount: u64,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    additional_accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_transfer_checked(
+        token_program.key,
+        from.to_account_info(),
+        mint.to_account_info(),
+        to.to_account_info(),
+        authority,
+        additional_accounts,
+        amount,
+        mint.decimals,
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+/// Transfers out of a PDA token account that is its own authority.
+pub fn transfer_signed<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    amount: u64,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    additional_accounts: &[AccountInfo<'info>],
+    seeds: &[&[u8]],
+) -> Result<()> {
+    transfer_tokens(
+        from,
+        to,
+        from.to_account_info(),
+        amount,
+        mint,
+        token_program,
+        additional_accounts,
+        &[seeds],
+    )
+}
+
+/// Closes a PDA token account that is its own authority.
+/// Closes a token account that signs with `seeds`. Transfer fees withheld in it are
+/// harvested to `mint` first, which anyone may do and which Token-2022 requires.
+pub fn close_signed<'info>(
+    account: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    destination: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    if withheld_transfer_fees(account)? > 0 {
+        let harvest_cpi_accounts = HarvestWithheldTokensToMint {
+            token_program_id: token_program.to_account_info(),
+            mint: mint.to_account_info(),
+        };
+        harvest_withheld_tokens_to_mint(
+            CpiContext::new(token_program.to_account_info(), harvest_cpi_accounts),
+            vec![account.to_account_info()],
+        )?;
+    }
+
+    let close_cpi_accounts = CloseAccount {
+        account: account.to_account_info(),
+        destination,
+        authority: account.to_account_info(),
+    };
+
+    let signer_seeds = &[seeds];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        close_cpi_accounts,
+        signer_seeds,
+    );
+
+    close_account(cpi_ctx)
+}
+
+/// Creates a program-owned PDA with room for `space` bytes of data after the discriminator.
+/// Like Anchor's `init`, an address someone already sent lamports to is topped up to the
+/// rent-exempt minimum, then allocated and assigned.
+pub fn create_program_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    space: usize,
+    seeds: &[&[u8]],
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let space = ANCHOR_DISCRIMINATOR + space;
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(space);
+    let current_lamports = account.lamports();
+    if current_lamports == 0 {
+        return create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                &[seeds],
+            ),
+            rent_exempt_minimum,
+            space as u64,
+            &crate::ID,
+        );
+    }
+
+    let top_up = rent_exempt_minimum.saturating_sub(current_lamports);
+    if top_up > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate {
+                account_to_allocate: account.clone(),
+            },
+            &[seeds],
+        ),
+        space as u64,
+    )?;
+    assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Assign {
+                account_to_assign: account.clone(),
+            },
+            &[seeds],
+        ),
+        &crate::ID,
+    )
+}
+
+/// Closes an account owned by this program that cannot be loaded as an `Account`.
+pub fn close_program_account<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    let destination_lamports = destination.lamports();
+    **destination.lamports.borrow_mut() = destination_lamports
+        .checked_add(account.lamports())
+        .ok_or(crate::error::ErrorCode::CalculationOverflow)?;
+    **account.lamports.borrow_mut() = 0;
+
+    account.assign(&System::id());
+    account.realloc(0, false).map_err(Into::into)
+}
+
+/// Closes what a vesting account leaves behind once it is closed itself: its grant
+/// counters, whose rent goes to `owner`.
+pub fn close_leftover_accounts<'info>(
+    vesting_account: Pubkey,
+    owner: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    for account in accounts {
+        require_keys_eq!(*account.owner, crate::ID, ErrorCode::InvalidLeftoverAccount);
+        let grant_counter = GrantCounter::try_deserialize(&mut &account.try_borrow_data()?[..])
+            .map_err(|_| ErrorCode::InvalidLeftoverAccount)?;
+        require_keys_eq!(
+            grant_counter.vesting_account,
+            vesting_account,
+            ErrorCode::InvalidLeftoverAccount
+        );
+        close_program_account(account, owner)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,490 @@
+//! Vesting of native SOL. The treasury is a system-owned PDA derived from the same
+//! `vesting_treasury` seeds as a token treasury; it holds the vested SOL as lamports
+//! on top of its rent-exempt minimum and pays out through system-program transfers
+//! signed with those seeds.
+//!
+//! Grants are ordinary `EmployeeAccount`s, so schedules, milestones, pausing,
+//! beneficiary transfers and `close_employee_account` work unchanged. Claims,
+//! revocations and withdrawals follow the token instructions of the same name. Token
+//! instructions reject these accounts, and these reject token vesting accounts, because
+//! each side's treasury fails the other's account checks.
+//!
+//! Native SOL accounts deliberately cover only the single-owner, one-grant-at-a-time
+//! flow:
+//! - `set_admins` rejects them. Proposals execute through the token instructions, and a
+//!   lamport variant of every `execute_*` would double the multisig surface.
+//! - `claim_all` and `crank_claim` have no counterpart, so `set_claim_delegate` rejects
+//!   these accounts and only the beneficiary claims, one grant per `claim_sol`.
+//!
+//! Companies that need any of these vest wrapped SOL instead: a token vesting account
+//! for the native mint gets every instruction, and beneficiaries unwrap on their side.
+
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::{
+    emit_event,
+    error::ErrorCode,
+    events::{
+        GrantRevoked, TokensClaimed, TreasuryFunded, UnallocatedWithdrawn, VestingAccountCreated,
+    },
+    EmployeeAccount, GrantCounter, GrantParams, VestingAccount, ANCHOR_DISCRIMINATOR,
+    NATIVE_SOL_MINT, VESTING_VERSION,
+};
+
+use super::{
+    check_unallocated_amount, close_leftover_accounts, grant_created, load_grant_counter,
+    open_grant, stop_vesting, withdraw_vested_amount,
+};
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(company_name: String)]
+pub struct CreateSolVestingAccount<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+    init,
+    payer = signer,
+    space = ANCHOR_DISCRIMINATOR + VestingAccount::INIT_SPACE,
+    seeds = [b"vesting", signer.key().as_ref(), company_name.as_bytes()],
+    bump
+  )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+      mut,
+      seeds = [b"vesting_treasury", signer.key().as_ref(), company_name.as_bytes()],
+      bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct FundSolTreasury<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(mut, address = vesting_account.treasury_token_account)]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(grant: GrantParams)]
+pub struct CreateSolEmployeeAccount<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub beneficiary: SystemAccount<'info>,
+    #[account(
+    mut,
+    has_one = owner,
+    constraint = vesting_account.is_single_owner() @ ErrorCode::MultisigRequired,
+    constraint = !vesting_account.paused @ ErrorCode::VestingPaused,
+  )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(address = vesting_account.treasury_token_account)]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(
+    init_if_needed,
+    payer = owner,
+    space = ANCHOR_DISCRIMINATOR + GrantCounter::INIT_SPACE,
+    seeds = [b"grant_counter", vesting_account.key().as_ref(), beneficiary.key().as_ref()],
+    bump
+  )]
+    pub grant_counter: Account<'info, GrantCounter>,
+
+    #[account(
+    init,
+    payer = owner,
+    space = ANCHOR_DISCRIMINATOR + EmployeeAccount::INIT_SPACE,
+    seeds = [b"employee_vesting", beneficiary.key().as_ref(), vesting_account.key().as_ref(), grant.grant_id.to_le_bytes().as_ref()],
+    bump
+  )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ClaimSol<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"employee_vesting", beneficiary.key().as_ref(), vesting_account.key().as_ref(), employee_account.grant_id.to_le_bytes().as_ref()],
+        bump = employee_account.bump,
+        has_one = beneficiary,
+        has_one = vesting_account,
+    )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+
+    #[account(
+        mut,
+        constraint = !vesting_account.paused @ ErrorCode::VestingPaused,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(mut, address = vesting_account.treasury_token_account)]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RevokeSolVesting<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        constraint = vesting_account.is_single_owner() @ ErrorCode::MultisigRequired,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"employee_vesting", employee_account.beneficiary.as_ref(), vesting_account.key().as_ref(), employee_account.grant_id.to_le_bytes().as_ref()],
+        bump = employee_account.bump,
+        has_one = vesting_account,
+    )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+
+    #[account(mut, address = vesting_account.treasury_token_account)]
+    pub treasury: SystemAccount<'info>,
+
+    /// Owner-chosen account that receives the unvested remainder.
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct WithdrawUnallocatedSol<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner,
+        constraint = vesting_account.is_single_owner() @ ErrorCode::MultisigRequired,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(mut, address = vesting_account.treasury_token_account)]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSolVestingAccount<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(mut, address = vesting_account.treasury_token_account)]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Lamports of the treasury above its rent-exempt minimum, the part grants can use.
+fn spendable_lamports(treasury: &SystemAccount) -> Result<u64> {
+    Ok(treasury
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(0)))
+}
+
+fn transfer_lamports_from_treasury<'info>(
+    vesting_account: &Account<'info, VestingAccount>,
+    treasury: &SystemAccount<'info>,
+    to: AccountInfo<'info>,
+    amount: u64,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let seeds = vesting_account.treasury_seeds();
+    transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Transfer {
+                from: treasury.to_account_info(),
+                to,
+            },
+            &[&seeds],
+        ),
+        amount,
+    )
+}
+
+/// Creates the vesting account and tops its treasury up to the rent-exempt minimum,
+/// so that it can hold any amount of SOL.
+pub fn save_sol_vesting_account(
+    ctx: Context<CreateSolVestingAccount>,
+    company_name: String,
+) -> Result<()> {
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+    let missing_rent = rent_exempt_minimum.saturating_sub(ctx.accounts.treasury.lamports());
+    if missing_rent > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.signer.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            missing_rent,
+        )?;
+    }
+
+    *ctx.accounts.vesting_account = VestingAccount {
+        owner: ctx.accounts.signer.key(),
+        pending_owner: None,
+        creator: ctx.accounts.signer.key(),
+        mint: NATIVE_SOL_MINT,
+        treasury_token_account: ctx.accounts.treasury.key(),
+        company_name,
+        committed_amount: 0,
+        total_deposited: 0,
+        active_grants: 0,
+        version: VESTING_VERSION,
+        legacy_vesting_account: None,
+        legacy_reserved: 0,
+        legacy_reserve_expires_at: 0,
+        admins: Vec::new(),
+        threshold: 0,
+        proposal_count: 0,
+        open_proposals: 0,
+        paused: false,
+        treasury_bump: ctx.bumps.treasury,
+        bump: ctx.bumps.vesting_account,
+    };
+
+    emit_event!(
+        ctx,
+        VestingAccountCreated {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            owner: ctx.accounts.vesting_account.owner,
+            mint: NATIVE_SOL_MINT,
+            company_name: ctx.accounts.vesting_account.company_name.clone(),
+        }
+    );
+    Ok(())
+}
+
+pub fn deposit_lamports(ctx: Context<FundSolTreasury>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let vesting_account = &mut ctx.accounts.vesting_account;
+    vesting_account.total_deposited = vesting_account
+        .total_deposited
+        .checked_add(amount)
+        .ok_or(ErrorCode::CalculationOverflow)?;
+
+    emit_event!(
+        ctx,
+        TreasuryFunded {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            funder: ctx.accounts.owner.key(),
+            amount,
+            total_deposited: ctx.accounts.vesting_account.total_deposited,
+        }
+    );
+    Ok(())
+}
+
+pub fn save_sol_employee_account(
+    ctx: Context<CreateSolEmployeeAccount>,
+    grant: GrantParams,
+) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    load_grant_counter(
+        &mut accounts.grant_counter,
+        accounts.vesting_account.key(),
+        accounts.beneficiary.key(),
+        ctx.bumps.grant_counter,
+    );
+
+    let employee_account = open_grant(
+        &mut accounts.vesting_account,
+        spendable_lamports(&accounts.treasury)?,
+        &mut accounts.grant_counter,
+        accounts.beneficiary.key(),
+        accounts.owner.key(),
+        grant,
+        ctx.bumps.employee_account,
+    )?;
+    accounts.employee_account.set_inner(employee_account);
+
+    emit_event!(
+        ctx,
+        grant_created(
+            ctx.accounts.employee_account.key(),
+            &ctx.accounts.employee_account
+        )
+    );
+    Ok(())
+}
+
+/// Pays everything vested and not yet withdrawn to the beneficiary's wallet.
+pub fn claim_vested_lamports(ctx: Context<ClaimSol>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let claimable_amount = withdraw_vested_amount(
+        &mut accounts.employee_account,
+        &mut accounts.vesting_account,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    transfer_lamports_from_treasury(
+        &accounts.vesting_account,
+        &accounts.treasury,
+        accounts.beneficiary.to_account_info(),
+        claimable_amount,
+        &accounts.system_program,
+    )?;
+
+    emit_event!(
+        ctx,
+        TokensClaimed {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            employee_account: ctx.accounts.employee_account.key(),
+            beneficiary: ctx.accounts.employee_account.beneficiary,
+            amount: claimable_amount,
+            transfer_fee: 0,
+            total_withdrawn: ctx.accounts.employee_account.total_withdrawn,
+        }
+    );
+    Ok(())
+}
+
+/// Stops the grant from vesting further and returns its unvested remainder to
+/// `destination`.
+pub fn revoke_sol_grant(ctx: Context<RevokeSolVesting>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let now = Clock::get()?.unix_timestamp;
+    let unvested_amount = stop_vesting(
+        &mut accounts.employee_account,
+        &mut accounts.vesting_account,
+        now,
+    )?;
+
+    if unvested_amount > 0 {
+        transfer_lamports_from_treasury(
+            &accounts.vesting_account,
+            &accounts.treasury,
+            accounts.destination.to_account_info(),
+            unvested_amount,
+            &accounts.system_program,
+        )?;
+    }
+
+    emit_event!(
+        ctx,
+        GrantRevoked {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            employee_account: ctx.accounts.employee_account.key(),
+            beneficiary: ctx.accounts.employee_account.beneficiary,
+            revoked_at: now,
+            returned_amount: unvested_amount,
+        }
+    );
+    Ok(())
+}
+
+pub fn withdraw_unallocated_lamports(
+    ctx: Context<WithdrawUnallocatedSol>,
+    amount: u64,
+) -> Result<()> {
+    check_unallocated_amount(
+        &ctx.accounts.vesting_account,
+        spendable_lamports(&ctx.accounts.treasury)?,
+        amount,
+    )?;
+
+    transfer_lamports_from_treasury(
+        &ctx.accounts.vesting_account,
+        &ctx.accounts.treasury,
+        ctx.accounts.destination.to_account_info(),
+        amount,
+        &ctx.accounts.system_program,
+    )?;
+
+    emit_event!(
+        ctx,
+        UnallocatedWithdrawn {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+        }
+    );
+    Ok(())
+}
+
+/// Closes the vesting account and returns the treasury's rent to the owner. Remaining
+/// accounts are the grant counters to close along with it, see `close_leftover_accounts`.
+pub fn close_empty_sol_vesting_account<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseSolVestingAccount<'info>>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.vesting_account.active_grants == 0,
+        ErrorCode::GrantsStillOpen
+    );
+    require!(
+        ctx.accounts.vesting_account.open_proposals == 0,
+        ErrorCode::ProposalsStillOpen
+    );
+    require!(
+        spendable_lamports(&ctx.accounts.treasury)? == 0,
+        ErrorCode::TreasuryNotEmpty
+    );
+
+    transfer_lamports_from_treasury(
+        &ctx.accounts.vesting_account,
+        &ctx.accounts.treasury,
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.treasury.lamports(),
+        &ctx.accounts.system_program,
+    )?;
+    close_leftover_accounts(
+        ctx.accounts.vesting_account.key(),
+        &ctx.accounts.owner.to_account_info(),
+        ctx.remaining_accounts,
+    )
+}
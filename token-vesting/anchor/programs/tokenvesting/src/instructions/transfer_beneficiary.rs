@@ -0,0 +1,192 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    emit_event, error::ErrorCode, events::BeneficiaryTransferred, ClaimPermission, EmployeeAccount,
+    GrantCounter, VestingAccount, ANCHOR_DISCRIMINATOR,
+};
+
+use super::load_grant_counter;
+
+/// Moves a grant to a new wallet with the consent of both the beneficiary and the owner.
+/// Open to the owner in multisig mode too: the beneficiary must sign and the grant's
+/// terms move unchanged, unlike `recover_beneficiary`.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(new_grant_id: u64)]
+pub struct TransferBeneficiary<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub beneficiary: Signer<'info>,
+
+    pub new_beneficiary: SystemAccount<'info>,
+
+    /// Receives the rent of the old grant account.
+    #[account(mut, address = employee_account.payer)]
+    pub rent_payer: SystemAccount<'info>,
+
+    #[account(has_one = owner)]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"employee_vesting", beneficiary.key().as_ref(), vesting_account.key().as_ref(), employee_account.grant_id.to_le_bytes().as_ref()],
+        bump = employee_account.bump,
+        has_one = beneficiary,
+        has_one = vesting_account,
+    )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + GrantCounter::INIT_SPACE,
+        seeds = [b"grant_counter", vesting_account.key().as_ref(), new_beneficiary.key().as_ref()],
+        bump
+    )]
+    pub new_grant_counter: Account<'info, GrantCounter>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + EmployeeAccount::INIT_SPACE,
+        seeds = [b"employee_vesting", new_beneficiary.key().as_ref(), vesting_account.key().as_ref(), new_grant_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub new_employee_account: Account<'info, EmployeeAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Recovery mode: the owner alone moves a grant whose beneficiary lost access to their wallet.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(new_grant_id: u64)]
+pub struct RecoverBeneficiary<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub new_beneficiary: SystemAccount<'info>,
+
+    /// Receives the rent of the old grant account.
+    #[account(mut, address = employee_account.payer)]
+    pub rent_payer: SystemAccount<'info>,
+
+    #[account(
+        has_one = owner,
+        constraint = vesting_account.is_single_owner() @ ErrorCode::MultisigRequired,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"employee_vesting", employee_account.beneficiary.as_ref(), vesting_account.key().as_ref(), employee_account.grant_id.to_le_bytes().as_ref()],
+        bump = employee_account.bump,
+        has_one = vesting_account,
+    )]
+    pub employee_account: Account<'info, EmployeeAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + GrantCounter::INIT_SPACE,
+        seeds = [b"grant_counter", vesting_account.key().as_ref(), new_beneficiary.key().as_ref()],
+        bump
+    )]
+    pub new_grant_counter: Account<'info, GrantCounter>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + EmployeeAccount::INIT_SPACE,
+        seeds = [b"employee_vesting", new_beneficiary.key().as_ref(), vesting_account.key().as_ref(), new_grant_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub new_employee_account: Account<'info, EmployeeAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn move_to_new_beneficiary(ctx: Context<TransferBeneficiary>, new_grant_id: u64) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    load_grant_counter(
+        &mut accounts.new_grant_counter,
+        accounts.vesting_account.key(),
+        accounts.new_beneficiary.key(),
+        ctx.bumps.new_grant_counter,
+    );
+    accounts.new_grant_counter.next_grant(new_grant_id)?;
+
+    let event = move_grant(
+        &accounts.employee_account,
+        &mut accounts.new_employee_account,
+        accounts.new_beneficiary.key(),
+        accounts.owner.key(),
+        new_grant_id,
+        ctx.bumps.new_employee_account,
+    )?;
+
+    emit_event!(ctx, event);
+    Ok(())
+}
+
+pub fn recover_to_new_beneficiary(
+    ctx: Context<RecoverBeneficiary>,
+    new_grant_id: u64,
+) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    load_grant_counter(
+        &mut accounts.new_grant_counter,
+        accounts.vesting_account.key(),
+        accounts.new_beneficiary.key(),
+        ctx.bumps.new_grant_counter,
+    );
+    accounts.new_grant_counter.next_grant(new_grant_id)?;
+
+    let event = move_grant(
+        &accounts.employee_account,
+        &mut accounts.new_employee_account,
+        accounts.new_beneficiary.key(),
+        accounts.owner.key(),
+        new_grant_id,
+        ctx.bumps.new_employee_account,
+    )?;
+
+    emit_event!(ctx, event);
+    Ok(())
+}
+
+/// Copies the remaining schedule, including `total_withdrawn`, into the new grant account.
+fn move_grant(
+    employee_account: &Account<EmployeeAccount>,
+    new_employee_account: &mut Account<EmployeeAccount>,
+    new_beneficiary: Pubkey,
+    payer: Pubkey,
+    new_grant_id: u64,
+    bump: u8,
+) -> Result<BeneficiaryTransferred> {
+    require_keys_neq!(
+        new_beneficiary,
+        employee_account.beneficiary,
+        ErrorCode::SameBeneficiary
+    );
+
+    new_employee_account.set_inner(EmployeeAccount {
+        beneficiary: new_beneficiary,
+        grant_id: new_grant_id,
+        payer,
+        // The previous beneficiary's delegate has no say over the new beneficiary's grant.
+        claim_permission: ClaimPermission::BeneficiaryOnly,
+        bump,
+        ..(**employee_account).clone()
+    });
+
+    Ok(BeneficiaryTransferred {
+        vesting_account: employee_account.vesting_account,
+        previous_beneficiary: employee_account.beneficiary,
+        new_beneficiary,
+        employee_account: new_employee_account.key(),
+    })
+}
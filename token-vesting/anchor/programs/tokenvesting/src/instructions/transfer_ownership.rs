@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    emit_event,
+    error::ErrorCode,
+    events::{OwnerTransferAccepted, OwnerTransferCancelled, OwnerTransferProposed},
+    VestingAccount,
+};
+
+/// Step one of an owner handover: the current owner names their successor. Only in
+/// single-owner mode; in multisig mode the admins replace the owner through a `SetOwner`
+/// proposal.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ProposeOwner<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        constraint = vesting_account.is_single_owner() @ ErrorCode::MultisigRequired,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+}
+
+/// Step two: the proposed owner signs to take over, proving they control the key.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AcceptOwner<'info> {
+    pub new_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = vesting_account.pending_owner == Some(new_owner.key()) @ ErrorCode::NotPendingOwner,
+        constraint = vesting_account.is_single_owner() @ ErrorCode::MultisigRequired,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+}
+
+/// Open in multisig mode too, since dropping a pending handover changes no one's powers.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CancelOwnerTransfer<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub vesting_account: Account<'info, VestingAccount>,
+}
+
+/// Replaces any earlier proposal.
+pub fn save_pending_owner(ctx: Context<ProposeOwner>, new_owner: Pubkey) -> Result<()> {
+    ctx.accounts.vesting_account.pending_owner = Some(new_owner);
+
+    emit_event!(
+        ctx,
+        OwnerTransferProposed {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            owner: ctx.accounts.owner.key(),
+            pending_owner: new_owner,
+        }
+    );
+    Ok(())
+}
+
+pub fn take_ownership(ctx: Context<AcceptOwner>) -> Result<()> {
+    let vesting_account = &mut ctx.accounts.vesting_account;
+    let previous_owner = vesting_account.owner;
+    vesting_account.owner = ctx.accounts.new_owner.key();
+    vesting_account.pending_owner = None;
+
+    emit_event!(
+        ctx,
+        OwnerTransferAccepted {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            previous_owner,
+            new_owner: ctx.accounts.new_owner.key(),
+        }
+    );
+    Ok(())
+}
+
+pub fn clear_pending_owner(ctx: Context<CancelOwnerTransfer>) -> Result<()> {
+    let pending_owner = ctx
+        .accounts
+        .vesting_account
+        .pending_owner
+        .take()
+        .ok_or(ErrorCode::NoPendingOwner)?;
+
+    emit_event!(
+        ctx,
+        OwnerTransferCancelled {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            owner: ctx.accounts.owner.key(),
+            pending_owner,
+        }
+    );
+    Ok(())
+}
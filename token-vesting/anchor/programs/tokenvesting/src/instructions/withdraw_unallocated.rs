@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{emit_event, error::ErrorCode, events::UnallocatedWithdrawn, VestingAccount};
+
+use super::transfer_from_treasury;
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct WithdrawUnallocated<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner,
+        has_one = treasury_token_account,
+        has_one = mint,
+        constraint = vesting_account.is_single_owner() @ ErrorCode::MultisigRequired,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(mut)]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Remaining accounts are forwarded to the token program for transfer-hook mints.
+pub fn withdraw_from_treasury<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawUnallocated<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let event = withdraw_unallocated_tokens(
+        &ctx.accounts.vesting_account,
+        &ctx.accounts.treasury_token_account,
+        &ctx.accounts.destination_token_account,
+        amount,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+    )?;
+
+    emit_event!(ctx, event);
+    Ok(())
+}
+
+/// Moves `amount` of the treasury tokens not committed to any grant to
+/// `destination_token_account`.
+pub fn withdraw_unallocated_tokens<'info>(
+    vesting_account: &Account<'info, VestingAccount>,
+    treasury_token_account: &InterfaceAccount<'info, TokenAccount>,
+    destination_token_account: &InterfaceAccount<'info, TokenAccount>,
+    amount: u64,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    additional_accounts: &[AccountInfo<'info>],
+) -> Result<UnallocatedWithdrawn> {
+    check_unallocated_amount(vesting_account, treasury_token_account.amount, amount)?;
+
+    transfer_from_treasury(
+        vesting_account,
+        treasury_token_account,
+        destination_token_account,
+        amount,
+        mint,
+        token_program,
+        additional_accounts,
+    )?;
+
+    Ok(UnallocatedWithdrawn {
+        vesting_account: vesting_account.key(),
+        destination: destination_token_account.key(),
+        amount,
+    })
+}
+
+/// Checks `amount` is positive and not committed to any grant out of `treasury_balance`.
+pub fn check_unallocated_amount(
+    vesting_account: &VestingAccount,
+    treasury_balance: u64,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let unallocated_amount = treasury_balance.saturating_sub(vesting_account.committed_amount);
+    require!(
+        amount <= unallocated_amount,
+        ErrorCode::InsufficientUnallocatedBalance
+    );
+    Ok(())
+}
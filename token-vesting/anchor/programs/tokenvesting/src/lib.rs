@@ -1,12 +1,19 @@
 #![allow(clippy::result_large_err)]
 
+pub mod constants;
+pub mod error;
+pub mod events;
+pub mod extensions;
+pub mod instructions;
+pub mod math;
+pub mod state;
+
 use anchor_lang::prelude::*;
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
-};
 
-pub const ANCHOR_DISCRIMINATOR: usize = 8;
+pub use constants::*;
+pub use instructions::*;
+pub use state::*;
+
 declare_id!("J1RkQLCjdpaRww8CD5TXu9nmkp2cd4odZ7AkH3j17Bko");
 
 #[program]
@@ -17,232 +24,239 @@ pub mod tokenvesting {
         context: Context<CreateVestingAccount>,
         company_name: String,
     ) -> Result<()> {
-        *context.accounts.vesting_account = VestingAccount {
-            owner: context.accounts.signer.key(),
-            mint: context.accounts.mint.key(),
-            treasury_token_account: context.accounts.treasury_token_account.key(),
-            company_name,
-            treasury_bump: context.bumps.treasury_token_account,
-            bump: context.bumps.vesting_account,
-        };
-        Ok(())
+        create_vesting_account::save_vesting_account(context, company_name)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn create_employee_account(
         context: Context<CreateEmployeeAccount>,
+        grant_id: u64,
         start_time: i64,
         end_time: i64,
         cliff_time: i64,
         total_amount: u64,
+        schedule: VestingSchedule,
+        milestones: Vec<u64>,
     ) -> Result<()> {
-        *context.accounts.employee_account = EmployeeAccount {
-            beneficiary: context.accounts.beneficiary.key(),
+        create_employee_account::save_employee_account(
+            context,
+            grant_id,
             start_time,
             end_time,
             cliff_time,
-            vesting_account: context.accounts.vesting_account.key(),
             total_amount,
-            total_withdrawn: 0,
-            bump: context.bumps.employee_account,
-        };
-        Ok(())
-    }
-
-    pub fn claim_tokens(context: Context<ClaimTokens>, _company_name: String) -> Result<()> {
-        let employee_account = &mut context.accounts.employee_account;
-        let now = Clock::get()?.unix_timestamp;
-        if now < employee_account.cliff_time {
-            return Err(ErrorCode::ClaimNotAvailableYet.into());
-        }
-
-        let time_since_start = now.saturating_sub(employee_account.start_time);
-        let total_vesting_time = employee_account
-            .end_time
-            .saturating_sub(employee_account.start_time);
-
-        if total_vesting_time <= 0 {
-            return Err(ErrorCode::InvalidVestingPeriod.into());
-        }
-
-        let vested_amount = if now >= employee_account.end_time {
-            employee_account.total_amount
-        } else {
-            match employee_account
-                .total_amount
-                .checked_mul(time_since_start as u64)
-            {
-                Some(product) => product / total_vesting_time as u64,
-                None => return Err(ErrorCode::CalculationOverflow.into()),
-            }
-        };
-
-        let claimable_amount = vested_amount.saturating_sub(employee_account.total_withdrawn);
-
-        if claimable_amount <= 0 {
-            return Err(ErrorCode::NothingToClaim.into());
-        }
-
-        let transfer_cpi_accounts = TransferChecked {
-            authority: context.accounts.treasury_token_account.to_account_info(),
-            from: context.accounts.treasury_token_account.to_account_info(),
-            to: context.accounts.employee_token_account.to_account_info(),
-            mint: context.accounts.mint.to_account_info(),
-        };
-
-        let seeds = &[
-            b"vesting_treasury",
-            context.accounts.vesting_account.company_name.as_bytes(),
-            &[context.accounts.vesting_account.treasury_bump],
-        ];
-
-        let signer_seeds = &[&seeds[..]];
-
-        let cpi_ctx = CpiContext::new_with_signer(
-            context.accounts.token_program.to_account_info(),
-            transfer_cpi_accounts,
-            signer_seeds,
-        );
-
-        employee_account.total_withdrawn = employee_account
-            .total_withdrawn
-            .saturating_add(claimable_amount);
-
-        transfer_checked(cpi_ctx, claimable_amount, context.accounts.mint.decimals)
+            schedule,
+            milestones,
+        )
     }
-}
 
-#[derive(Accounts)]
-#[instruction(company_name: String)]
-pub struct ClaimTokens<'info> {
-    #[account(mut)]
-    pub beneficiary: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [b"employee_vesting", beneficiary.key().as_ref(),vesting_account.key().as_ref()],
-        bump = employee_account.bump,
-        has_one = beneficiary,
-        has_one = vesting_account,
-    )]
-    pub employee_account: Account<'info, EmployeeAccount>,
-
-    #[account(
-        mut,
-        seeds = [company_name.as_ref()],
-        bump = vesting_account.bump,
-        has_one = treasury_token_account,
-        has_one = mint,
-    )]
-    pub vesting_account: Account<'info, VestingAccount>,
-
-    #[account(mut)]
-    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
-
-    pub mint: InterfaceAccount<'info, Mint>,
-
-    #[account(
-        init_if_needed,
-        payer = beneficiary,
-        associated_token::mint = mint,
-        associated_token::authority = beneficiary,
-        associated_token::token_program = token_program,
-    )]
-    pub employee_token_account: InterfaceAccount<'info, TokenAccount>,
-
-    pub token_program: Interface<'info, TokenInterface>,
-    pub system_program: Program<'info, System>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-}
+    pub fn create_employee_accounts_batch<'info>(
+        context: Context<'_, '_, 'info, 'info, CreateEmployeeAccountsBatch<'info>>,
+        entries: Vec<GrantEntry>,
+    ) -> Result<()> {
+        create_employee_accounts_batch::save_employee_accounts_batch(context, entries)
+    }
 
-#[derive(Accounts)]
-#[instruction(company_name: String)]
-pub struct CreateVestingAccount<'info> {
-    #[account(mut)]
-    pub signer: Signer<'info>,
-
-    #[account(
-    init,
-    payer = signer,
-    space = ANCHOR_DISCRIMINATOR + VestingAccount::INIT_SPACE,
-    seeds = [company_name.as_ref()],
-    bump
-  )]
-    pub vesting_account: Account<'info, VestingAccount>,
-
-    pub mint: InterfaceAccount<'info, Mint>,
-
-    #[account(
-      init,
-      payer = signer,
-      token::mint = mint,
-      token::authority = treasury_token_account,
-      seeds = [b"vesting_treasury", company_name.as_bytes()],
-      bump
-    )]
-    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
-
-    pub system_program: Program<'info, System>,
-    pub token_program: Interface<'info, TokenInterface>,
-}
+    pub fn attest_milestone(context: Context<AttestMilestone>, milestone_index: u8) -> Result<()> {
+        attest_milestone::attest_grant_milestone(context, milestone_index)
+    }
 
-#[account]
-#[derive(InitSpace)]
-pub struct VestingAccount {
-    pub owner: Pubkey,
-    pub mint: Pubkey,
-    pub treasury_token_account: Pubkey,
-    #[max_len(50)]
-    pub company_name: String,
-    pub treasury_bump: u8,
-    pub bump: u8,
-}
+    pub fn claim_tokens<'info>(
+        context: Context<'_, '_, 'info, 'info, ClaimTokens<'info>>,
+    ) -> Result<()> {
+        claim_tokens::claim_vested_tokens(context)
+    }
 
-#[derive(Accounts)]
-pub struct CreateEmployeeAccount<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub beneficiary: SystemAccount<'info>,
-    #[account(
-    has_one = owner,
-  )]
-    pub vesting_account: Account<'info, VestingAccount>,
-
-    #[account(
-    init,
-    payer = owner,
-    space = ANCHOR_DISCRIMINATOR + EmployeeAccount::INIT_SPACE,
-    seeds = [b"employee_vesting", beneficiary.key().as_ref(),vesting_account.key().as_ref()],
-    bump
-  )]
-    pub employee_account: Account<'info, EmployeeAccount>,
-
-    pub system_program: Program<'info, System>,
-}
+    pub fn claim_all<'info>(
+        context: Context<'_, '_, 'info, 'info, ClaimAll<'info>>,
+        grant_count: u8,
+    ) -> Result<()> {
+        claim_all::claim_all_grants(context, grant_count)
+    }
 
-#[account]
-#[derive(InitSpace)]
-pub struct EmployeeAccount {
-    pub beneficiary: Pubkey,
-    pub start_time: i64,
-    pub end_time: i64,
-    pub cliff_time: i64,
-    pub vesting_account: Pubkey,
-    pub total_amount: u64,
-    pub total_withdrawn: u64,
-    pub bump: u8,
-}
+    pub fn set_claim_delegate(
+        context: Context<SetClaimDelegate>,
+        claim_permission: ClaimPermission,
+    ) -> Result<()> {
+        crank_claim::save_claim_permission(context, claim_permission)
+    }
+
+    pub fn crank_claim<'info>(
+        context: Context<'_, '_, 'info, 'info, CrankClaim<'info>>,
+    ) -> Result<()> {
+        crank_claim::claim_for_beneficiary(context)
+    }
+
+    pub fn get_vesting_status(
+        context: Context<GetVestingStatus>,
+        timestamp: i64,
+    ) -> Result<VestingStatus> {
+        get_vesting_status::vesting_status_at(context, timestamp)
+    }
+
+    pub fn revoke_employee_vesting<'info>(
+        context: Context<'_, '_, 'info, 'info, RevokeEmployeeVesting<'info>>,
+    ) -> Result<()> {
+        revoke_employee_vesting::revoke_vesting(context)
+    }
+
+    pub fn fund_treasury<'info>(
+        context: Context<'_, '_, 'info, 'info, FundTreasury<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        fund_treasury::deposit_into_treasury(context, amount)
+    }
+
+    pub fn withdraw_unallocated<'info>(
+        context: Context<'_, '_, 'info, 'info, WithdrawUnallocated<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        withdraw_unallocated::withdraw_from_treasury(context, amount)
+    }
+
+    pub fn pause(context: Context<SetPaused>) -> Result<()> {
+        pause_vesting::set_paused(context, true)
+    }
+
+    pub fn unpause(context: Context<SetPaused>) -> Result<()> {
+        pause_vesting::set_paused(context, false)
+    }
+
+    pub fn propose_owner(context: Context<ProposeOwner>, new_owner: Pubkey) -> Result<()> {
+        transfer_ownership::save_pending_owner(context, new_owner)
+    }
+
+    pub fn accept_owner(context: Context<AcceptOwner>) -> Result<()> {
+        transfer_ownership::take_ownership(context)
+    }
+
+    pub fn cancel_owner_transfer(context: Context<CancelOwnerTransfer>) -> Result<()> {
+        transfer_ownership::clear_pending_owner(context)
+    }
+
+    pub fn set_admins(
+        context: Context<SetAdmins>,
+        admins: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        set_admins::save_admins(context, admins, threshold)
+    }
+
+    pub fn create_proposal(context: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+        create_proposal::save_proposal(context, action)
+    }
+
+    pub fn approve_proposal(context: Context<ApproveProposal>) -> Result<()> {
+        create_proposal::record_approval(context)
+    }
+
+    pub fn cancel_proposal(context: Context<CancelProposal>) -> Result<()> {
+        create_proposal::discard_proposal(context)
+    }
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Claim not available yet")]
-    ClaimNotAvailableYet,
+    pub fn execute_create_grant(context: Context<ExecuteCreateGrant>) -> Result<()> {
+        execute_proposal::open_proposed_grant(context)
+    }
 
-    #[msg("Invalid vesting period")]
-    InvalidVestingPeriod,
+    pub fn execute_revoke_grant<'info>(
+        context: Context<'_, '_, 'info, 'info, ExecuteRevokeGrant<'info>>,
+    ) -> Result<()> {
+        execute_proposal::revoke_proposed_grant(context)
+    }
 
-    #[msg("Calculation overflow")]
-    CalculationOverflow,
+    pub fn execute_attest_milestone(context: Context<ExecuteAttestMilestone>) -> Result<()> {
+        execute_proposal::attest_proposed_milestone(context)
+    }
 
-    #[msg("Nothing to claim")]
-    NothingToClaim,
+    pub fn execute_withdraw_unallocated<'info>(
+        context: Context<'_, '_, 'info, 'info, ExecuteWithdrawUnallocated<'info>>,
+    ) -> Result<()> {
+        execute_proposal::withdraw_proposed_amount(context)
+    }
+
+    pub fn execute_set_admins(context: Context<ExecuteSetAdmins>) -> Result<()> {
+        execute_proposal::apply_proposed_admins(context)
+    }
+
+    pub fn execute_set_owner(context: Context<ExecuteSetOwner>) -> Result<()> {
+        execute_proposal::apply_proposed_owner(context)
+    }
+
+    pub fn close_employee_account(context: Context<CloseEmployeeAccount>) -> Result<()> {
+        close_employee_account::close_settled_grant(context)
+    }
+
+    pub fn close_vesting_account<'info>(
+        context: Context<'_, '_, 'info, 'info, CloseVestingAccount<'info>>,
+    ) -> Result<()> {
+        close_vesting_account::close_empty_vesting_account(context)
+    }
+
+    pub fn create_sol_vesting_account(
+        context: Context<CreateSolVestingAccount>,
+        company_name: String,
+    ) -> Result<()> {
+        sol_vesting::save_sol_vesting_account(context, company_name)
+    }
+
+    pub fn fund_sol_treasury(context: Context<FundSolTreasury>, amount: u64) -> Result<()> {
+        sol_vesting::deposit_lamports(context, amount)
+    }
+
+    pub fn create_sol_employee_account(
+        context: Context<CreateSolEmployeeAccount>,
+        grant: GrantParams,
+    ) -> Result<()> {
+        sol_vesting::save_sol_employee_account(context, grant)
+    }
+
+    pub fn claim_sol(context: Context<ClaimSol>) -> Result<()> {
+        sol_vesting::claim_vested_lamports(context)
+    }
+
+    pub fn revoke_sol_vesting(context: Context<RevokeSolVesting>) -> Result<()> {
+        sol_vesting::revoke_sol_grant(context)
+    }
+
+    pub fn withdraw_unallocated_sol(
+        context: Context<WithdrawUnallocatedSol>,
+        amount: u64,
+    ) -> Result<()> {
+        sol_vesting::withdraw_unallocated_lamports(context, amount)
+    }
+
+    pub fn close_sol_vesting_account<'info>(
+        context: Context<'_, '_, 'info, 'info, CloseSolVestingAccount<'info>>,
+    ) -> Result<()> {
+        sol_vesting::close_empty_sol_vesting_account(context)
+    }
+
+    pub fn migrate_vesting_account<'info>(
+        context: Context<'_, '_, 'info, 'info, MigrateVestingAccount<'info>>,
+        company_name: String,
+    ) -> Result<()> {
+        migrate_vesting_account::migrate_legacy_vesting_account(context, company_name)
+    }
+
+    pub fn migrate_employee_account(context: Context<MigrateEmployeeAccount>) -> Result<()> {
+        migrate_vesting_account::migrate_legacy_grant(context)
+    }
+
+    pub fn release_legacy_reserve(context: Context<ReleaseLegacyReserve>) -> Result<()> {
+        migrate_vesting_account::release_unclaimed_legacy_reserve(context)
+    }
+
+    pub fn transfer_beneficiary(
+        context: Context<TransferBeneficiary>,
+        new_grant_id: u64,
+    ) -> Result<()> {
+        transfer_beneficiary::move_to_new_beneficiary(context, new_grant_id)
+    }
+
+    pub fn recover_beneficiary(
+        context: Context<RecoverBeneficiary>,
+        new_grant_id: u64,
+    ) -> Result<()> {
+        transfer_beneficiary::recover_to_new_beneficiary(context, new_grant_id)
+    }
 }
@@ -0,0 +1,311 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, Milestone, VestingSchedule};
+
+/// Amount of `total_amount` vested at `now`.
+///
+/// Nothing vests before `cliff_time`, and everything has vested from `end_time` on.
+/// In between, the vested share is `total_amount * vested_time / (end_time - start_time)`,
+/// computed in u128 and rounded down, so the beneficiary can never get ahead of the
+/// schedule and any rounding remainder is released at `end_time`. For stepped schedules
+/// `vested_time` is rounded down to whole completed periods first.
+pub fn vested_amount(
+    total_amount: u64,
+    start_time: i64,
+    end_time: i64,
+    cliff_time: i64,
+    schedule: VestingSchedule,
+    now: i64,
+) -> Result<u64> {
+    if end_time <= start_time {
+        return Err(ErrorCode::InvalidVestingPeriod.into());
+    }
+
+    if now < cliff_time || now <= start_time {
+        return Ok(0);
+    }
+
+    if now >= end_time {
+        return Ok(total_amount);
+    }
+
+    let time_since_start = (now as i128 - start_time as i128) as u128;
+    let total_vesting_time = (end_time as i128 - start_time as i128) as u128;
+
+    let vested_time = match schedule {
+        VestingSchedule::Linear => time_since_start,
+        VestingSchedule::Stepped { period } => {
+            if period <= 0 {
+                return Err(ErrorCode::InvalidStepPeriod.into());
+            }
+            time_since_start - time_since_start % period as u128
+        }
+        // Milestone grants vest through `milestone_vested_amount`.
+        VestingSchedule::Milestone => return Err(ErrorCode::InvalidMilestones.into()),
+    };
+
+    let vested = total_amount as u128 * vested_time / total_vesting_time;
+    u64::try_from(vested).map_err(|_| ErrorCode::CalculationOverflow.into())
+}
+
+/// Sum of the tranches attested by `now`, or nothing before the `floor` time.
+pub fn milestone_vested_amount(milestones: &[Milestone], floor: i64, now: i64) -> Result<u64> {
+    if now < floor {
+        return Ok(0);
+    }
+
+    milestones
+        .iter()
+        .filter(|milestone| {
+            milestone
+                .attested_at
+                .is_some_and(|attested_at| attested_at <= now)
+        })
+        .try_fold(0u64, |vested, milestone| {
+            vested.checked_add(milestone.amount)
+        })
+        .ok_or(ErrorCode::CalculationOverflow.into())
+}
+
+/// Earliest time after `now` at which `vested_amount` grows, or `None` once
+/// everything has vested.
+pub fn next_unlock_time(
+    total_amount: u64,
+    start_time: i64,
+    end_time: i64,
+    cliff_time: i64,
+    schedule: VestingSchedule,
+    now: i64,
+) -> Result<Option<i64>> {
+    let vested = vested_amount(
+        total_amount,
+        start_time,
+        end_time,
+        cliff_time,
+        schedule,
+        now,
+    )?;
+    if vested >= total_amount {
+        return Ok(None);
+    }
+
+    // Smallest vested time at which the schedule, ignoring the cliff, passes `vested`.
+    let total_vesting_time = (end_time as i128 - start_time as i128) as u128;
+    let target = (vested as u128 + 1) * total_vesting_time;
+    let vested_time = match schedule {
+        VestingSchedule::Linear => target.div_ceil(total_amount as u128),
+        VestingSchedule::Stepped { period } => {
+            if period <= 0 {
+                return Err(ErrorCode::InvalidStepPeriod.into());
+            }
+            let period = period as u128;
+            target.div_ceil(total_amount as u128 * period) * period
+        }
+        VestingSchedule::Milestone => return Err(ErrorCode::InvalidMilestones.into()),
+    };
+
+    let unlock_time = start_time as i128 + vested_time.min(total_vesting_time) as i128;
+    let unlock_time = i64::try_from(unlock_time).map_err(|_| ErrorCode::CalculationOverflow)?;
+    Ok(Some(unlock_time.max(cliff_time)))
+}
+
+/// Earliest time after `now` at which an attested tranche becomes claimable.
+/// Unattested tranches have no unlock time.
+pub fn milestone_next_unlock_time(milestones: &[Milestone], floor: i64, now: i64) -> Option<i64> {
+    milestones
+        .iter()
+        .filter_map(|milestone| milestone.attested_at)
+        .map(|attested_at| attested_at.max(floor))
+        .filter(|unlock_time| *unlock_time > now)
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const MONTH: i64 = 30 * 24 * 60 * 60;
+
+    #[test]
+    fn nothing_vests_before_cliff() {
+        assert_eq!(
+            vested_amount(1_000, 0, 100, 25, VestingSchedule::Linear, 24).unwrap(),
+            0
+        );
+        assert_eq!(
+            vested_amount(1_000, 0, 100, 25, VestingSchedule::Linear, 25).unwrap(),
+            250
+        );
+    }
+
+    #[test]
+    fn linear_vesting_rounds_down() {
+        assert_eq!(
+            vested_amount(10, 0, 3, 0, VestingSchedule::Linear, 1).unwrap(),
+            3
+        );
+        assert_eq!(
+            vested_amount(10, 0, 3, 0, VestingSchedule::Linear, 2).unwrap(),
+            6
+        );
+        assert_eq!(
+            vested_amount(10, 0, 3, 0, VestingSchedule::Linear, 3).unwrap(),
+            10
+        );
+    }
+
+    #[test]
+    fn everything_vests_at_end() {
+        assert_eq!(
+            vested_amount(7, 10, 20, 10, VestingSchedule::Linear, 20).unwrap(),
+            7
+        );
+        assert_eq!(
+            vested_amount(7, 10, 20, 10, VestingSchedule::Linear, i64::MAX).unwrap(),
+            7
+        );
+    }
+
+    #[test]
+    fn stepped_vesting_unlocks_whole_periods() {
+        let schedule = VestingSchedule::Stepped { period: MONTH };
+        let end_time = 12 * MONTH;
+        assert_eq!(
+            vested_amount(1_200, 0, end_time, 0, schedule, MONTH - 1).unwrap(),
+            0
+        );
+        assert_eq!(
+            vested_amount(1_200, 0, end_time, 0, schedule, MONTH).unwrap(),
+            100
+        );
+        assert_eq!(
+            vested_amount(1_200, 0, end_time, 0, schedule, 5 * MONTH + 7).unwrap(),
+            500
+        );
+        assert_eq!(
+            vested_amount(1_200, 0, end_time, 0, schedule, end_time).unwrap(),
+            1_200
+        );
+    }
+
+    #[test]
+    fn large_grants_do_not_overflow() {
+        // 10 billion tokens with 9 decimals over four years overflows u64 intermediates.
+        let total_amount = 10_000_000_000 * 1_000_000_000;
+        let end_time = 48 * MONTH;
+        let vested = vested_amount(
+            total_amount,
+            0,
+            end_time,
+            0,
+            VestingSchedule::Linear,
+            end_time / 2,
+        )
+        .unwrap();
+        assert_eq!(vested, total_amount / 2);
+    }
+
+    #[test]
+    fn rejects_empty_vesting_period() {
+        assert!(vested_amount(1, 5, 5, 5, VestingSchedule::Linear, 5).is_err());
+    }
+
+    #[test]
+    fn milestones_vest_once_attested_and_past_floor() {
+        let milestones = [
+            Milestone {
+                amount: 100,
+                attested_at: Some(10),
+            },
+            Milestone {
+                amount: 200,
+                attested_at: None,
+            },
+            Milestone {
+                amount: 300,
+                attested_at: Some(40),
+            },
+        ];
+        assert_eq!(milestone_vested_amount(&milestones, 20, 15).unwrap(), 0);
+        assert_eq!(milestone_vested_amount(&milestones, 20, 20).unwrap(), 100);
+        assert_eq!(milestone_vested_amount(&milestones, 20, 40).unwrap(), 400);
+    }
+
+    #[test]
+    fn next_unlock_is_the_first_time_more_vests() {
+        let unlock = |schedule, now| next_unlock_time(10, 0, 100, 25, schedule, now).unwrap();
+        assert_eq!(unlock(VestingSchedule::Linear, 0), Some(25));
+        assert_eq!(unlock(VestingSchedule::Linear, 25), Some(30));
+        assert_eq!(unlock(VestingSchedule::Linear, 95), Some(100));
+        assert_eq!(unlock(VestingSchedule::Linear, 100), None);
+        assert_eq!(unlock(VestingSchedule::Stepped { period: 40 }, 0), Some(40));
+        assert_eq!(
+            unlock(VestingSchedule::Stepped { period: 40 }, 80),
+            Some(100)
+        );
+    }
+
+    fn schedule_strategy() -> impl Strategy<Value = VestingSchedule> {
+        prop_oneof![
+            Just(VestingSchedule::Linear),
+            (1..=MONTH).prop_map(|period| VestingSchedule::Stepped { period }),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn next_unlock_vests_more_and_nothing_vests_before_it(
+            total_amount in 1..=u64::MAX,
+            duration in 1..=4 * 12 * MONTH,
+            cliff_offset in 0..=4 * 12 * MONTH,
+            schedule in schedule_strategy(),
+            now in -MONTH..=5 * 12 * MONTH,
+        ) {
+            let cliff_time = cliff_offset.min(duration);
+            let vested_at = |time| vested_amount(total_amount, 0, duration, cliff_time, schedule, time).unwrap();
+            let vested = vested_at(now);
+            match next_unlock_time(total_amount, 0, duration, cliff_time, schedule, now).unwrap() {
+                Some(unlock_time) => {
+                    prop_assert!(unlock_time > now);
+                    prop_assert!(vested_at(unlock_time) > vested);
+                    prop_assert_eq!(vested_at(unlock_time - 1), vested);
+                }
+                None => prop_assert_eq!(vested, total_amount),
+            }
+        }
+
+        #[test]
+        fn claims_never_exceed_total_and_never_decrease(
+            total_amount in any::<u64>(),
+            start_time in -(1i64 << 40)..(1i64 << 40),
+            duration in 1..(10 * 12 * MONTH),
+            cliff_offset in 0..(10 * 12 * MONTH),
+            schedule in schedule_strategy(),
+            mut claim_times in prop::collection::vec(-(1i64 << 41)..(1i64 << 41), 1..32),
+        ) {
+            let end_time = start_time + duration;
+            let cliff_time = start_time + cliff_offset.min(duration);
+            claim_times.sort_unstable();
+
+            let mut total_withdrawn = 0u64;
+            let mut previous_vested = 0u64;
+            for now in claim_times {
+                let vested =
+                    vested_amount(total_amount, start_time, end_time, cliff_time, schedule, now)
+                        .unwrap();
+                prop_assert!(vested >= previous_vested);
+                prop_assert!(vested <= total_amount);
+
+                total_withdrawn += vested - total_withdrawn;
+                prop_assert!(total_withdrawn <= total_amount);
+                previous_vested = vested;
+            }
+
+            let fully_vested =
+                vested_amount(total_amount, start_time, end_time, cliff_time, schedule, end_time)
+                    .unwrap();
+            prop_assert_eq!(fully_vested, total_amount);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::{
+    math::{milestone_next_unlock_time, milestone_vested_amount, next_unlock_time, vested_amount},
+    state::vesting_account::load_legacy_account,
+    MAX_MILESTONES,
+};
+
+/// Terms of a new grant.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct GrantParams {
+    pub grant_id: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub cliff_time: i64,
+    pub total_amount: u64,
+    pub schedule: VestingSchedule,
+    /// Tranche amounts of a milestone grant, empty otherwise.
+    #[max_len(MAX_MILESTONES)]
+    pub milestones: Vec<u64>,
+}
+
+/// How vested tokens unlock between `start_time` and `end_time`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum VestingSchedule {
+    /// Unlocks continuously, second by second.
+    Linear,
+    /// Unlocks in whole periods of `period` seconds counted from `start_time`.
+    Stepped { period: i64 },
+    /// Unlocks one tranche of `milestones` at a time as the owner attests to it. The
+    /// cliff acts as a time floor: attested tranches stay locked until `cliff_time`.
+    Milestone,
+}
+
+/// A tranche of a milestone grant.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct Milestone {
+    pub amount: u64,
+    pub attested_at: Option<i64>,
+}
+
+/// Who besides the beneficiary may trigger a claim with `crank_claim`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum ClaimPermission {
+    BeneficiaryOnly,
+    Delegate { delegate: Pubkey },
+    Permissionless,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct EmployeeAccount {
+    pub beneficiary: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub cliff_time: i64,
+    pub vesting_account: Pubkey,
+    /// Distinguishes this grant from the beneficiary's other grants in the same company.
+    pub grant_id: u64,
+    pub total_amount: u64,
+    pub total_withdrawn: u64,
+    /// Token-2022 transfer fees withheld from claims; the beneficiary received
+    /// `total_withdrawn - total_transfer_fees`.
+    pub total_transfer_fees: u64,
+    pub schedule: VestingSchedule,
+    /// Tranches of a `VestingSchedule::Milestone` grant; empty for time-based grants.
+    #[max_len(MAX_MILESTONES)]
+    pub milestones: Vec<Milestone>,
+    /// Paid the rent for this account and gets it back when the account is closed.
+    pub payer: Pubkey,
+    pub claim_permission: ClaimPermission,
+    /// Set once the owner revokes the grant; vesting stops accruing at this timestamp.
+    pub revoked_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl EmployeeAccount {
+    /// Amount vested at `now`, frozen at `revoked_at` for revoked grants.
+    pub fn vested_amount(&self, now: i64) -> Result<u64> {
+        let now = match self.revoked_at {
+            Some(revoked_at) => now.min(revoked_at),
+            None => now,
+        };
+
+        match self.schedule {
+            VestingSchedule::Milestone => {
+                milestone_vested_amount(&self.milestones, self.cliff_time, now)
+            }
+            schedule => vested_amount(
+                self.total_amount,
+                self.start_time,
+                self.end_time,
+                self.cliff_time,
+                schedule,
+                now,
+            ),
+        }
+    }
+
+    /// Earliest time after `now` at which more of the grant vests, if any is known.
+    pub fn next_unlock_time(&self, now: i64) -> Result<Option<i64>> {
+        let unlock_time = match self.schedule {
+            VestingSchedule::Milestone => {
+                milestone_next_unlock_time(&self.milestones, self.cliff_time, now)
+            }
+            schedule => next_unlock_time(
+                self.total_amount,
+                self.start_time,
+                self.end_time,
+                self.cliff_time,
+                schedule,
+                now,
+            )?,
+        };
+
+        Ok(match self.revoked_at {
+            Some(revoked_at) => unlock_time.filter(|unlock_time| *unlock_time <= revoked_at),
+            None => unlock_time,
+        })
+    }
+
+    /// Whether nothing is left to vest or claim: fully withdrawn, or revoked with
+    /// everything vested before the revocation withdrawn.
+    pub fn is_settled(&self) -> Result<bool> {
+        let final_amount = match self.revoked_at {
+            Some(revoked_at) => self.vested_amount(revoked_at)?,
+            None => self.total_amount,
+        };
+        Ok(self.total_withdrawn >= final_amount)
+    }
+}
+
+/// Layout of grants created before `VESTING_VERSION`, derived from
+/// `[b"employee_vesting", beneficiary, vesting_account]`; read only to migrate them.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyEmployeeAccount {
+    pub beneficiary: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub cliff_time: i64,
+    pub vesting_account: Pubkey,
+    pub total_amount: u64,
+    pub total_withdrawn: u64,
+    pub bump: u8,
+}
+
+impl LegacyEmployeeAccount {
+    pub fn load(account_info: &AccountInfo) -> Result<Self> {
+        load_legacy_account(account_info, &EmployeeAccount::DISCRIMINATOR)
+    }
+}
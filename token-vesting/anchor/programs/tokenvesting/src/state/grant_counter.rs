@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+/// Hands out grant ids so a beneficiary can hold several grants from the same company.
+#[account]
+#[derive(InitSpace)]
+pub struct GrantCounter {
+    pub beneficiary: Pubkey,
+    pub vesting_account: Pubkey,
+    /// Id the next grant for this beneficiary must use.
+    pub grant_count: u64,
+    pub bump: u8,
+}
+
+impl GrantCounter {
+    /// Checks `grant_id` is the next free id and reserves it.
+    pub fn next_grant(&mut self, grant_id: u64) -> Result<()> {
+        require_eq!(
+            grant_id,
+            self.grant_count,
+            crate::error::ErrorCode::InvalidGrantId
+        );
+        self.grant_count += 1;
+        Ok(())
+    }
+}
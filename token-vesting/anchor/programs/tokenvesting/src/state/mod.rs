@@ -0,0 +1,11 @@
+pub mod employee_account;
+pub use employee_account::*;
+
+pub mod grant_counter;
+pub use grant_counter::*;
+
+pub mod proposal;
+pub use proposal::*;
+
+pub mod vesting_account;
+pub use vesting_account::*;
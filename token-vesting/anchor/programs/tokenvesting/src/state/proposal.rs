@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::{GrantParams, MAX_ADMINS};
+
+/// What a proposal does once enough admins approved it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum ProposalAction {
+    CreateGrant {
+        beneficiary: Pubkey,
+        grant: GrantParams,
+    },
+    RevokeGrant {
+        employee_account: Pubkey,
+        destination_token_account: Pubkey,
+    },
+    AttestMilestone {
+        employee_account: Pubkey,
+        milestone_index: u8,
+    },
+    WithdrawUnallocated {
+        destination_token_account: Pubkey,
+        amount: u64,
+    },
+    /// Replaces the admin set; an empty set with a zero threshold returns the
+    /// vesting account to single-owner mode.
+    SetAdmins {
+        #[max_len(MAX_ADMINS)]
+        admins: Vec<Pubkey>,
+        threshold: u8,
+    },
+    /// Hands the owner role, which keeps pausing and closing the account, to
+    /// `new_owner` and drops any pending handover.
+    SetOwner { new_owner: Pubkey },
+}
+
+impl ProposalAction {
+    /// Id of the grant a `CreateGrant` proposal opens, used to derive its address.
+    pub fn grant_id(&self) -> u64 {
+        match self {
+            ProposalAction::CreateGrant { grant, .. } => grant.grant_id,
+            _ => 0,
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    pub vesting_account: Pubkey,
+    pub proposal_id: u64,
+    /// Admin who created the proposal; gets the rent back once it is executed or cancelled.
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    #[max_len(MAX_ADMINS)]
+    pub approvals: Vec<Pubkey>,
+    pub bump: u8,
+}
@@ -0,0 +1,124 @@
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::{MAX_ADMINS, NATIVE_SOL_MINT};
+
+#[account]
+#[derive(InitSpace)]
+pub struct VestingAccount {
+    pub owner: Pubkey,
+    /// Proposed next owner, who takes over once they call `accept_owner`.
+    pub pending_owner: Option<Pubkey>,
+    /// Signer of `create_vesting_account`; part of the PDA seeds, so it never changes.
+    pub creator: Pubkey,
+    /// `NATIVE_SOL_MINT` for accounts that vest native SOL.
+    pub mint: Pubkey,
+    /// Token account holding the treasury, or for native SOL the system-owned PDA
+    /// holding it as lamports. Either way derived from the same `vesting_treasury` seeds.
+    pub treasury_token_account: Pubkey,
+    #[max_len(50)]
+    pub company_name: String,
+    /// Treasury tokens promised to grants and not yet claimed or returned.
+    pub committed_amount: u64,
+    /// Tokens deposited through `fund_treasury` over the account's lifetime.
+    pub total_deposited: u64,
+    /// Grants created under this account that have not been closed yet.
+    pub active_grants: u64,
+    /// Seed derivation version, see `VESTING_VERSION`.
+    pub version: u8,
+    /// Legacy account this one was migrated from; its grants can be moved over with
+    /// `migrate_employee_account`.
+    pub legacy_vesting_account: Option<Pubkey>,
+    /// Part of `committed_amount` holding the migrated legacy treasury for the legacy
+    /// grants still to be migrated. Each grant takes its unclaimed remainder out of it.
+    pub legacy_reserved: u64,
+    /// When the owner may release what is left of `legacy_reserved`, see
+    /// `release_legacy_reserve`.
+    pub legacy_reserve_expires_at: i64,
+    /// M-of-N admin set. With a `threshold` of zero the account is in single-owner
+    /// mode; otherwise grants, revocations and withdrawals go through proposals
+    /// that need `threshold` approvals from `admins`.
+    #[max_len(MAX_ADMINS)]
+    pub admins: Vec<Pubkey>,
+    pub threshold: u8,
+    /// Proposals created so far; the next proposal's id.
+    pub proposal_count: u64,
+    /// Proposals neither executed nor cancelled yet. The account only closes without any,
+    /// so that no proposal outlives it into a re-created account with the same address.
+    pub open_proposals: u64,
+    /// While set, claims and new grants are rejected. Vesting keeps accruing, so
+    /// beneficiaries can claim everything they missed once the account is unpaused.
+    pub paused: bool,
+    pub treasury_bump: u8,
+    pub bump: u8,
+}
+
+impl VestingAccount {
+    pub fn is_single_owner(&self) -> bool {
+        self.threshold == 0
+    }
+
+    pub fn is_native_sol(&self) -> bool {
+        self.mint == NATIVE_SOL_MINT
+    }
+
+    /// Approvals that still count: those of keys that are admins right now.
+    pub fn approval_count(&self, approvals: &[Pubkey]) -> usize {
+        approvals
+            .iter()
+            .filter(|approver| self.admins.contains(approver))
+            .count()
+    }
+
+    /// Signer seeds of the `vesting_treasury` token account.
+    pub fn treasury_seeds(&self) -> [&[u8]; 4] {
+        [
+            b"vesting_treasury",
+            self.creator.as_ref(),
+            self.company_name.as_bytes(),
+            std::slice::from_ref(&self.treasury_bump),
+        ]
+    }
+}
+
+/// Layout of vesting accounts created before `VESTING_VERSION`, read only to migrate them.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyVestingAccount {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub treasury_token_account: Pubkey,
+    pub company_name: String,
+    pub treasury_bump: u8,
+    pub bump: u8,
+}
+
+impl LegacyVestingAccount {
+    pub fn load(account_info: &AccountInfo) -> Result<Self> {
+        load_legacy_account(account_info, &VestingAccount::DISCRIMINATOR)
+    }
+
+    pub fn treasury_seeds(&self) -> [&[u8]; 3] {
+        [
+            b"vesting_treasury",
+            self.company_name.as_bytes(),
+            std::slice::from_ref(&self.treasury_bump),
+        ]
+    }
+}
+
+/// Reads an account of this program whose layout predates its current `#[account]` type.
+pub(crate) fn load_legacy_account<T: AnchorDeserialize>(
+    account_info: &AccountInfo,
+    discriminator: &[u8],
+) -> Result<T> {
+    require_keys_eq!(
+        *account_info.owner,
+        crate::ID,
+        ErrorCode::AccountOwnedByWrongProgram
+    );
+    let data = account_info.try_borrow_data()?;
+    require!(
+        data.get(..8) == Some(discriminator),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    T::deserialize(&mut &data[8..]).map_err(|_| ErrorCode::AccountDidNotDeserialize.into())
+}
@@ -0,0 +1,959 @@
+//! Instruction builders and fixtures for a vesting account and its grants.
+
+use tokenvesting::{ClaimPermission, EmployeeAccount, GrantCounter, Milestone, VestingAccount};
+
+use super::*;
+
+/// A vesting account with its mint, owner and funded treasury.
+pub struct Company {
+    pub owner: Pubkey,
+    pub name: String,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub vesting_account: Pubkey,
+    pub treasury: Pubkey,
+    pub owner_token_account: Pubkey,
+}
+
+impl Company {
+    /// Creates the vesting account for a fresh mint, mints `owner_balance` tokens to
+    /// the owner and deposits `treasury_amount` of them.
+    pub fn setup(
+        bank: &mut Bank,
+        token_program: Pubkey,
+        owner_balance: u64,
+        treasury_amount: u64,
+    ) -> Self {
+        let mint = bank.create_mint(token_program);
+        Self::setup_with_mint(bank, token_program, mint, owner_balance, treasury_amount)
+    }
+
+    pub fn setup_with_mint(
+        bank: &mut Bank,
+        token_program: Pubkey,
+        mint: Pubkey,
+        owner_balance: u64,
+        treasury_amount: u64,
+    ) -> Self {
+        let owner = bank.funded_wallet();
+        let name = "Acme".to_string();
+        let company = Self::derive(owner, name, mint, token_program);
+        bank.process(&[company.create_vesting_account_ix()], &[owner])
+            .unwrap();
+
+        let owner_token_account = bank.token_account(token_program, mint, owner);
+        let company = Self {
+            owner_token_account,
+            ..company
+        };
+        if owner_balance > 0 {
+            bank.mint_to(token_program, mint, owner_token_account, owner_balance);
+        }
+        if treasury_amount > 0 {
+            bank.process(&[company.fund_treasury_ix(treasury_amount)], &[owner])
+                .unwrap();
+        }
+        company
+    }
+
+    /// Creates a native SOL vesting account and deposits `treasury_lamports`.
+    pub fn setup_native(bank: &mut Bank, treasury_lamports: u64) -> Self {
+        let owner = bank.funded_wallet();
+        bank.airdrop(owner, treasury_lamports);
+        let company = Self::derive(
+            owner,
+            "Acme".to_string(),
+            tokenvesting::NATIVE_SOL_MINT,
+            system_program::ID,
+        );
+        bank.send(company.create_sol_vesting_account_ix(), &[owner])
+            .unwrap();
+        if treasury_lamports > 0 {
+            bank.send(company.fund_sol_treasury_ix(treasury_lamports), &[owner])
+                .unwrap();
+        }
+        company
+    }
+
+    pub fn derive(owner: Pubkey, name: String, mint: Pubkey, token_program: Pubkey) -> Self {
+        let (vesting_account, _) = Pubkey::find_program_address(
+            &[b"vesting", owner.as_ref(), name.as_bytes()],
+            &tokenvesting::ID,
+        );
+        let (treasury, _) = Pubkey::find_program_address(
+            &[b"vesting_treasury", owner.as_ref(), name.as_bytes()],
+            &tokenvesting::ID,
+        );
+        Self {
+            owner,
+            name,
+            mint,
+            token_program,
+            vesting_account,
+            treasury,
+            owner_token_account: get_associated_token_address_with_program_id(
+                &owner,
+                &mint,
+                &token_program,
+            ),
+        }
+    }
+
+    pub fn state(&self, bank: &Bank) -> VestingAccount {
+        bank.anchor_account(&self.vesting_account)
+    }
+
+    pub fn employee_account(&self, beneficiary: Pubkey, grant_id: u64) -> Pubkey {
+        employee_account_address(self.vesting_account, beneficiary, grant_id)
+    }
+
+    pub fn grant_counter(&self, beneficiary: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"grant_counter",
+                self.vesting_account.as_ref(),
+                beneficiary.as_ref(),
+            ],
+            &tokenvesting::ID,
+        )
+        .0
+    }
+
+    pub fn grant(&self, bank: &Bank, beneficiary: Pubkey, grant_id: u64) -> EmployeeAccount {
+        bank.anchor_account(&self.employee_account(beneficiary, grant_id))
+    }
+
+    pub fn beneficiary_token_account(&self, beneficiary: Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(&beneficiary, &self.mint, &self.token_program)
+    }
+
+    pub fn proposal(&self, proposal_id: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"proposal",
+                self.vesting_account.as_ref(),
+                &proposal_id.to_le_bytes(),
+            ],
+            &tokenvesting::ID,
+        )
+        .0
+    }
+
+    pub fn create_vesting_account_ix(&self) -> Instruction {
+        instruction(
+            tokenvesting::accounts::CreateVestingAccount {
+                signer: self.owner,
+                vesting_account: self.vesting_account,
+                mint: self.mint,
+                treasury_token_account: self.treasury,
+                system_program: system_program::ID,
+                token_program: self.token_program,
+            },
+            tokenvesting::instruction::CreateVestingAccount {
+                company_name: self.name.clone(),
+            },
+        )
+    }
+
+    pub fn fund_treasury_ix(&self, amount: u64) -> Instruction {
+        instruction(
+            tokenvesting::accounts::FundTreasury {
+                owner: self.owner,
+                vesting_account: self.vesting_account,
+                owner_token_account: self.owner_token_account,
+                treasury_token_account: self.treasury,
+                mint: self.mint,
+                token_program: self.token_program,
+            },
+            tokenvesting::instruction::FundTreasury { amount },
+        )
+    }
+
+    pub fn create_grant_ix(&self, beneficiary: Pubkey, grant: GrantParams) -> Instruction {
+        instruction(
+            tokenvesting::accounts::CreateEmployeeAccount {
+                owner: self.owner,
+                beneficiary,
+                vesting_account: self.vesting_account,
+                treasury_token_account: self.treasury,
+                grant_counter: self.grant_counter(beneficiary),
+                employee_account: self.employee_account(beneficiary, grant.grant_id),
+                system_program: system_program::ID,
+            },
+            tokenvesting::instruction::CreateEmployeeAccount {
+                grant_id: grant.grant_id,
+                start_time: grant.start_time,
+                end_time: grant.end_time,
+                cliff_time: grant.cliff_time,
+                total_amount: grant.total_amount,
+                schedule: grant.schedule,
+                milestones: grant.milestones,
+            },
+        )
+    }
+
+    /// Creates a grant signed by the owner, panicking on failure.
+    pub fn create_grant(&self, bank: &mut Bank, beneficiary: Pubkey, grant: GrantParams) {
+        bank.process(&[self.create_grant_ix(beneficiary, grant)], &[self.owner])
+            .unwrap();
+    }
+
+    pub fn claim_ix(&self, beneficiary: Pubkey, grant_id: u64) -> Instruction {
+        instruction(
+            tokenvesting::accounts::ClaimTokens {
+                beneficiary,
+                employee_account: self.employee_account(beneficiary, grant_id),
+                vesting_account: self.vesting_account,
+                treasury_token_account: self.treasury,
+                mint: self.mint,
+                employee_token_account: self.beneficiary_token_account(beneficiary),
+                token_program: self.token_program,
+                system_program: system_program::ID,
+                associated_token_program: spl_associated_token_account::ID,
+            },
+            tokenvesting::instruction::ClaimTokens {},
+        )
+    }
+
+    pub fn claim_all_ix(&self, beneficiary: Pubkey, grant_ids: &[u64]) -> Instruction {
+        let mut instruction = instruction(
+            tokenvesting::accounts::ClaimAll {
+                beneficiary,
+                vesting_account: self.vesting_account,
+                treasury_token_account: self.treasury,
+                mint: self.mint,
+                employee_token_account: self.beneficiary_token_account(beneficiary),
+                token_program: self.token_program,
+                system_program: system_program::ID,
+                associated_token_program: spl_associated_token_account::ID,
+            },
+            tokenvesting::instruction::ClaimAll {
+                grant_count: grant_ids.len() as u8,
+            },
+        );
+        instruction
+            .accounts
+            .extend(grant_ids.iter().map(|grant_id| {
+                AccountMeta::new(self.employee_account(beneficiary, *grant_id), false)
+            }));
+        instruction
+    }
+
+    pub fn set_claim_delegate_ix(
+        &self,
+        beneficiary: Pubkey,
+        grant_id: u64,
+        claim_permission: ClaimPermission,
+    ) -> Instruction {
+        instruction(
+            tokenvesting::accounts::SetClaimDelegate {
+                beneficiary,
+                vesting_account: self.vesting_account,
+                employee_account: self.employee_account(beneficiary, grant_id),
+            },
+            tokenvesting::instruction::SetClaimDelegate { claim_permission },
+        )
+    }
+
+    pub fn crank_claim_ix(
+        &self,
+        caller: Pubkey,
+        beneficiary: Pubkey,
+        grant_id: u64,
+    ) -> Instruction {
+        instruction(
+            tokenvesting::accounts::CrankClaim {
+                caller,
+                beneficiary,
+                employee_account: self.employee_account(beneficiary, grant_id),
+                vesting_account: self.vesting_account,
+                treasury_token_account: self.treasury,
+                mint: self.mint,
+                employee_token_account: self.beneficiary_token_account(beneficiary),
+                token_program: self.token_program,
+                system_program: system_program::ID,
+                associated_token_program: spl_associated_token_account::ID,
+            },
+            tokenvesting::instruction::CrankClaim {},
+        )
+    }
+
+    pub fn attest_milestone_ix(
+        &self,
+        beneficiary: Pubkey,
+        grant_id: u64,
+        milestone_index: u8,
+    ) -> Instruction {
+        instruction(
+            tokenvesting::accounts::AttestMilestone {
+                owner: self.owner,
+                vesting_account: self.vesting_account,
+                employee_account: self.employee_account(beneficiary, grant_id),
+            },
+            tokenvesting::instruction::AttestMilestone { milestone_index },
+        )
+    }
+
+    pub fn get_vesting_status_ix(
+        &self,
+        beneficiary: Pubkey,
+        grant_id: u64,
+        timestamp: i64,
+    ) -> Instruction {
+        instruction(
+            tokenvesting::accounts::GetVestingStatus {
+                employee_account: self.employee_account(beneficiary, grant_id),
+            },
+            tokenvesting::instruction::GetVestingStatus { timestamp },
+        )
+    }
+
+    pub fn revoke_ix(
+        &self,
+        beneficiary: Pubkey,
+        grant_id: u64,
+        destination: Pubkey,
+    ) -> Instruction {
+        instruction(
+            tokenvesting::accounts::RevokeEmployeeVesting {
+                owner: self.owner,
+                vesting_account: self.vesting_account,
+                employee_account: self.employee_account(beneficiary, grant_id),
+                treasury_token_account: self.treasury,
+                mint: self.mint,
+                destination_token_account: destination,
+                token_program: self.token_program,
+            },
+            tokenvesting::instruction::RevokeEmployeeVesting {},
+        )
+    }
+
+    pub fn withdraw_unallocated_ix(&self, destination: Pubkey, amount: u64) -> Instruction {
+        instruction(
+            tokenvesting::accounts::WithdrawUnallocated {
+                owner: self.owner,
+                vesting_account: self.vesting_account,
+                treasury_token_account: self.treasury,
+                mint: self.mint,
+                destination_token_account: destination,
+                token_program: self.token_program,
+            },
+            tokenvesting::instruction::WithdrawUnallocated { amount },
+        )
+    }
+
+    pub fn set_paused_ix(&self, paused: bool) -> Instruction {
+        let accounts = tokenvesting::accounts::SetPaused {
+            owner: self.owner,
+            vesting_account: self.vesting_account,
+        };
+        if paused {
+            instruction(accounts, tokenvesting::instruction::Pause {})
+        } else {
+            instruction(accounts, tokenvesting::instruction::Unpause {})
+        }
+    }
+
+    pub fn propose_owner_ix(&self, new_owner: Pubkey) -> Instruction {
+        instruction(
+            tokenvesting::accounts::ProposeOwner {
+                owner: self.owner,
+                vesting_account: self.vesting_account,
+            },
+            tokenvesting::instruction::ProposeOwner { new_owner },
+        )
+    }
+
+    pub fn accept_owner_ix(&self, new_owner: Pubkey) -> Instruction {
+        instruction(
+            tokenvesting::accounts::AcceptOwner {
+                new_owner,
+                vesting_account: self.vesting_account,
+            },
+            tokenvesting::instruction::AcceptOwner {},
+        )
+    }
+
+    pub fn cancel_owner_transfer_ix(&self) -> Instruction {
+        instruction(
+            tokenvesting::accounts::CancelOwnerTransfer {
+                owner: self.owner,
+                vesting_account: self.vesting_account,
+            },
+            tokenvesting::instruction::CancelOwnerTransfer {},
+        )
+    }
+
+    pub fn set_admins_ix(&self, admins: Vec<Pubkey>, threshold: u8) -> Instruction {
+        instruction(
+            tokenvesting::accounts::SetAdmins {
+                owner: self.owner,
+                vesting_account: self.vesting_account,
+            },
+            tokenvesting::instruction::SetAdmins { admins, threshold },
+        )
+    }
+
+    pub fn create_proposal_ix(
+        &self,
+        bank: &Bank,
+        proposer: Pubkey,
+        action: tokenvesting::ProposalAction,
+    ) -> Instruction {
+        instruction(
+            tokenvesting::accounts::CreateProposal {
+                proposer,
+                vesting_account: self.vesting_account,
+                proposal: self.proposal(self.state(bank).proposal_count),
+                system_program: system_program::ID,
+            },
+            tokenvesting::instruction::CreateProposal { action },
+        )
+    }
+
+    pub fn approve_proposal_ix(&self, admin: Pubkey, proposal_id: u64) -> Instruction {
+        instruction(
+            tokenvesting::accounts::ApproveProposal {
+                admin,
+                vesting_account: self.vesting_account,
+                proposal: self.proposal(proposal_id),
+            },
+            tokenvesting::instruction::ApproveProposal {},
+        )
+    }
+
+    pub fn cancel_proposal_ix(&self, proposer: Pubkey, proposal_id: u64) -> Instruction {
+        instruction(
+            tokenvesting::accounts::CancelProposal {
+                proposer,
+                vesting_account: self.vesting_account,
+                proposal: self.proposal(proposal_id),
+            },
+            tokenvesting::instruction::CancelProposal {},
+        )
+    }
+
+    pub fn execute_create_grant_ix(
+        &self,
+        executor: Pubkey,
+        proposer: Pubkey,
+        proposal_id: u64,
+        beneficiary: Pubkey,
+        grant_id: u64,
+    ) -> Instruction {
+        instruction(
+            tokenvesting::accounts::ExecuteCreateGrant {
+                executor,
+                beneficiary,
+                vesting_account: self.vesting_account,
+                treasury_token_account: self.treasury,
+                proposer,
+                proposal: self.proposal(proposal_id),
+                grant_counter: self.grant_counter(beneficiary),
+                employee_account: self.employee_account(beneficiary, grant_id),
+                system_program: system_program::ID,
+            },
+            tokenvesting::instruction::ExecuteCreateGrant {},
+        )
+    }
+
+    pub fn execute_revoke_grant_ix(
+        &self,
+        proposer: Pubkey,
+        proposal_id: u64,
+        employee_account: Pubkey,
+        destination: Pubkey,
+    ) -> Instruction {
+        instruction(
+            tokenvesting::accounts::ExecuteRevokeGrant {
+                vesting_account: self.vesting_account,
+                employee_account,
+                treasury_token_account: self.treasury,
+                mint: self.mint,
+                destination_token_account: destination,
+                proposer,
+                proposal: self.proposal(proposal_id),
+                token_program: self.token_program,
+            },
+            tokenvesting::instruction::ExecuteRevokeGrant {},
+        )
+    }
+
+    pub fn execute_attest_milestone_ix(
+        &self,
+        proposer: Pubkey,
+        proposal_id: u64,
+        employee_account: Pubkey,
+    ) -> Instruction {
+        instruction(
+            tokenvesting::accounts::ExecuteAttestMilestone {
+                vesting_account: self.vesting_account,
+                employee_account,
+                proposer,
+                proposal: self.proposal(proposal_id),
+            },
+            tokenvesting::instruction::ExecuteAttestMilestone {},
+        )
+    }
+
+    pub fn execute_withdraw_unallocated_ix(
+        &self,
+        proposer: Pubkey,
+        proposal_id: u64,
+        destination: Pubkey,
+    ) -> Instruction {
+        instruction(
+            tokenvesting::accounts::ExecuteWithdrawUnallocated {
+                vesting_account: self.vesting_account,
+                treasury_token_account: self.treasury,
+                mint: self.mint,
+                destination_token_account: destination,
+                proposer,
+                proposal: self.proposal(proposal_id),
+                token_program: self.token_program,
+            },
+            tokenvesting::instruction::ExecuteWithdrawUnallocated {},
+        )
+    }
+
+    pub fn execute_set_admins_ix(&self, proposer: Pubkey, proposal_id: u64) -> Instruction {
+        instruction(
+            tokenvesting::accounts::ExecuteSetAdmins {
+                vesting_account: self.vesting_account,
+                proposer,
+                proposal: self.proposal(proposal_id),
+            },
+            tokenvesting::instruction::ExecuteSetAdmins {},
+        )
+    }
+
+    pub fn execute_set_owner_ix(&self, proposer: Pubkey, proposal_id: u64) -> Instruction {
+        instruction(
+            tokenvesting::accounts::ExecuteSetOwner {
+                vesting_account: self.vesting_account,
+                proposer,
+                proposal: self.proposal(proposal_id),
+            },
+            tokenvesting::instruction::ExecuteSetOwner {},
+        )
+    }
+
+    pub fn batch_ix(&self, entries: Vec<tokenvesting::GrantEntry>) -> Instruction {
+        let mut instruction = instruction(
+            tokenvesting::accounts::CreateEmployeeAccountsBatch {
+                owner: self.owner,
+                vesting_account: self.vesting_account,
+                treasury_token_account: self.treasury,
+                system_program: system_program::ID,
+            },
+            tokenvesting::instruction::CreateEmployeeAccountsBatch {
+                entries: entries.clone(),
+            },
+        );
+        for entry in &entries {
+            instruction.accounts.push(AccountMeta::new(
+                self.grant_counter(entry.beneficiary),
+                false,
+            ));
+            instruction.accounts.push(AccountMeta::new(
+                self.employee_account(entry.beneficiary, entry.grant.grant_id),
+                false,
+            ));
+        }
+        instruction
+    }
+
+    pub fn create_sol_vesting_account_ix(&self) -> Instruction {
+        instruction(
+            tokenvesting::accounts::CreateSolVestingAccount {
+                signer: self.owner,
+                vesting_account: self.vesting_account,
+                treasury: self.treasury,
+                system_program: system_program::ID,
+            },
+            tokenvesting::instruction::CreateSolVestingAccount {
+                company_name: self.name.clone(),
+            },
+        )
+    }
+
+    pub fn fund_sol_treasury_ix(&self, amount: u64) -> Instruction {
+        instruction(
+            tokenvesting::accounts::FundSolTreasury {
+                owner: self.owner,
+                vesting_account: self.vesting_account,
+                treasury: self.treasury,
+                system_program: system_program::ID,
+            },
+            tokenvesting::instruction::FundSolTreasury { amount },
+        )
+    }
+
+    pub fn create_sol_grant_ix(&self, beneficiary: Pubkey, grant: GrantParams) -> Instruction {
+        instruction(
+            tokenvesting::accounts::CreateSolEmployeeAccount {
+                owner: self.owner,
+                beneficiary,
+                vesting_account: self.vesting_account,
+                treasury: self.treasury,
+                grant_counter: self.grant_counter(beneficiary),
+                employee_account: self.employee_account(beneficiary, grant.grant_id),
+                system_program: system_program::ID,
+            },
+            tokenvesting::instruction::CreateSolEmployeeAccount { grant },
+        )
+    }
+
+    pub fn claim_sol_ix(&self, beneficiary: Pubkey, grant_id: u64) -> Instruction {
+        instruction(
+            tokenvesting::accounts::ClaimSol {
+                beneficiary,
+                employee_account: self.employee_account(beneficiary, grant_id),
+                vesting_account: self.vesting_account,
+                treasury: self.treasury,
+                system_program: system_program::ID,
+            },
+            tokenvesting::instruction::ClaimSol {},
+        )
+    }
+
+    pub fn revoke_sol_ix(
+        &self,
+        beneficiary: Pubkey,
+        grant_id: u64,
+        destination: Pubkey,
+    ) -> Instruction {
+        instruction(
+            tokenvesting::accounts::RevokeSolVesting {
+                owner: self.owner,
+                vesting_account: self.vesting_account,
+                employee_account: self.employee_account(beneficiary, grant_id),
+                treasury: self.treasury,
+                destination,
+                system_program: system_program::ID,
+            },
+            tokenvesting::instruction::RevokeSolVesting {},
+        )
+    }
+
+    pub fn withdraw_unallocated_sol_ix(&self, destination: Pubkey, amount: u64) -> Instruction {
+        instruction(
+            tokenvesting::accounts::WithdrawUnallocatedSol {
+                owner: self.owner,
+                vesting_account: self.vesting_account,
+                treasury: self.treasury,
+                destination,
+                system_program: system_program::ID,
+            },
+            tokenvesting::instruction::WithdrawUnallocatedSol { amount },
+        )
+    }
+
+    /// `leftovers` are the grant counters and pending proposals (each followed by its
+    /// proposer) to close along with the vesting account.
+    pub fn close_sol_vesting_account_ix(&self, leftovers: &[Pubkey]) -> Instruction {
+        let mut instruction = instruction(
+            tokenvesting::accounts::CloseSolVestingAccount {
+                owner: self.owner,
+                vesting_account: self.vesting_account,
+                treasury: self.treasury,
+                system_program: system_program::ID,
+            },
+            tokenvesting::instruction::CloseSolVestingAccount {},
+        );
+        instruction.accounts.extend(
+            leftovers
+                .iter()
+                .map(|leftover| AccountMeta::new(*leftover, false)),
+        );
+        instruction
+    }
+
+    pub fn close_employee_account_ix(
+        &self,
+        payer: Pubkey,
+        beneficiary: Pubkey,
+        grant_id: u64,
+    ) -> Instruction {
+        instruction(
+            tokenvesting::accounts::CloseEmployeeAccount {
+                payer,
+                vesting_account: self.vesting_account,
+                employee_account: self.employee_account(beneficiary, grant_id),
+            },
+            tokenvesting::instruction::CloseEmployeeAccount {},
+        )
+    }
+
+    /// `leftovers` as for `close_sol_vesting_account_ix`.
+    pub fn close_vesting_account_ix(&self, leftovers: &[Pubkey]) -> Instruction {
+        let mut instruction = instruction(
+            tokenvesting::accounts::CloseVestingAccount {
+                owner: self.owner,
+                vesting_account: self.vesting_account,
+                treasury_token_account: self.treasury,
+                mint: self.mint,
+                token_program: self.token_program,
+            },
+            tokenvesting::instruction::CloseVestingAccount {},
+        );
+        instruction.accounts.extend(
+            leftovers
+                .iter()
+                .map(|leftover| AccountMeta::new(*leftover, false)),
+        );
+        instruction
+    }
+
+    pub fn transfer_beneficiary_ix(
+        &self,
+        beneficiary: Pubkey,
+        grant_id: u64,
+        new_beneficiary: Pubkey,
+        new_grant_id: u64,
+    ) -> Instruction {
+        instruction(
+            tokenvesting::accounts::TransferBeneficiary {
+                beneficiary,
+                owner: self.owner,
+                new_beneficiary,
+                rent_payer: self.owner,
+                vesting_account: self.vesting_account,
+                employee_account: self.employee_account(beneficiary, grant_id),
+                new_grant_counter: self.grant_counter(new_beneficiary),
+                new_employee_account: self.employee_account(new_beneficiary, new_grant_id),
+                system_program: system_program::ID,
+            },
+            tokenvesting::instruction::TransferBeneficiary { new_grant_id },
+        )
+    }
+
+    pub fn recover_beneficiary_ix(
+        &self,
+        beneficiary: Pubkey,
+        grant_id: u64,
+        new_beneficiary: Pubkey,
+        new_grant_id: u64,
+    ) -> Instruction {
+        instruction(
+            tokenvesting::accounts::RecoverBeneficiary {
+                owner: self.owner,
+                new_beneficiary,
+                rent_payer: self.owner,
+                vesting_account: self.vesting_account,
+                employee_account: self.employee_account(beneficiary, grant_id),
+                new_grant_counter: self.grant_counter(new_beneficiary),
+                new_employee_account: self.employee_account(new_beneficiary, new_grant_id),
+                system_program: system_program::ID,
+            },
+            tokenvesting::instruction::RecoverBeneficiary { new_grant_id },
+        )
+    }
+}
+
+/// A vesting account in the layout used before `VESTING_VERSION`, derived from the
+/// company name alone.
+pub struct LegacyCompany {
+    pub name: String,
+    pub vesting_account: Pubkey,
+    pub treasury: Pubkey,
+}
+
+impl LegacyCompany {
+    pub fn derive(name: &str) -> Self {
+        let (vesting_account, _) =
+            Pubkey::find_program_address(&[name.as_bytes()], &tokenvesting::ID);
+        let (treasury, _) = Pubkey::find_program_address(
+            &[b"vesting_treasury", name.as_bytes()],
+            &tokenvesting::ID,
+        );
+        Self {
+            name: name.to_string(),
+            vesting_account,
+            treasury,
+        }
+    }
+
+    /// Writes the legacy vesting account, byte for byte as the pre-`VESTING_VERSION`
+    /// program laid it out, and its treasury holding `treasury_amount`.
+    pub fn install(
+        &self,
+        bank: &mut Bank,
+        owner: Pubkey,
+        mint: Pubkey,
+        token_program: Pubkey,
+        treasury_amount: u64,
+    ) {
+        let (_, bump) = Pubkey::find_program_address(&[self.name.as_bytes()], &tokenvesting::ID);
+        let (_, treasury_bump) = Pubkey::find_program_address(
+            &[b"vesting_treasury", self.name.as_bytes()],
+            &tokenvesting::ID,
+        );
+        // discriminator, owner, mint, treasury_token_account, company_name (max 50 bytes),
+        // treasury_bump, bump
+        let mut data = VestingAccount::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(owner.as_ref());
+        data.extend_from_slice(mint.as_ref());
+        data.extend_from_slice(self.treasury.as_ref());
+        data.extend_from_slice(&(self.name.len() as u32).to_le_bytes());
+        data.extend_from_slice(self.name.as_bytes());
+        data.extend_from_slice(&[treasury_bump, bump]);
+        data.resize(8 + 32 * 3 + 4 + 50 + 2, 0);
+        bank.set_account(
+            self.vesting_account,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: tokenvesting::ID,
+                ..Account::default()
+            },
+        );
+        bank.set_token_account(
+            self.treasury,
+            token_program,
+            mint,
+            self.treasury,
+            treasury_amount,
+        );
+    }
+
+    pub fn grant_address(&self, beneficiary: Pubkey) -> Pubkey {
+        self.grant_address_and_bump(beneficiary).0
+    }
+
+    fn grant_address_and_bump(&self, beneficiary: Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"employee_vesting",
+                beneficiary.as_ref(),
+                self.vesting_account.as_ref(),
+            ],
+            &tokenvesting::ID,
+        )
+    }
+
+    /// Writes a grant of the legacy vesting account in the pre-`VESTING_VERSION` layout.
+    pub fn install_grant(&self, bank: &mut Bank, grant: LegacyGrant) -> Pubkey {
+        let (key, bump) = self.grant_address_and_bump(grant.beneficiary);
+        // discriminator, beneficiary, start_time, end_time, cliff_time, vesting_account,
+        // total_amount, total_withdrawn, bump
+        let mut data = EmployeeAccount::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(grant.beneficiary.as_ref());
+        data.extend_from_slice(&grant.start_time.to_le_bytes());
+        data.extend_from_slice(&grant.end_time.to_le_bytes());
+        data.extend_from_slice(&grant.cliff_time.to_le_bytes());
+        data.extend_from_slice(self.vesting_account.as_ref());
+        data.extend_from_slice(&grant.total_amount.to_le_bytes());
+        data.extend_from_slice(&grant.total_withdrawn.to_le_bytes());
+        data.push(bump);
+        bank.set_account(
+            key,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: tokenvesting::ID,
+                ..Account::default()
+            },
+        );
+        key
+    }
+
+    pub fn migrate_vesting_account_ix(&self, company: &Company) -> Instruction {
+        instruction(
+            tokenvesting::accounts::MigrateVestingAccount {
+                owner: company.owner,
+                legacy_vesting_account: self.vesting_account,
+                legacy_treasury_token_account: self.treasury,
+                vesting_account: company.vesting_account,
+                mint: company.mint,
+                treasury_token_account: company.treasury,
+                system_program: system_program::ID,
+                token_program: company.token_program,
+            },
+            tokenvesting::instruction::MigrateVestingAccount {
+                company_name: self.name.clone(),
+            },
+        )
+    }
+
+    /// Migrates the legacy grant of `beneficiary` to the beneficiary's next grant id, with
+    /// `payer` paying for the new accounts.
+    pub fn migrate_employee_account_ix(
+        &self,
+        bank: &Bank,
+        company: &Company,
+        beneficiary: Pubkey,
+        payer: Pubkey,
+    ) -> Instruction {
+        let grant_counter = company.grant_counter(beneficiary);
+        let grant_id = match bank.account(&grant_counter) {
+            Some(_) => {
+                bank.anchor_account::<GrantCounter>(&grant_counter)
+                    .grant_count
+            }
+            None => 0,
+        };
+        instruction(
+            tokenvesting::accounts::MigrateEmployeeAccount {
+                payer,
+                owner: company.owner,
+                vesting_account: company.vesting_account,
+                treasury_token_account: company.treasury,
+                beneficiary,
+                legacy_employee_account: self.grant_address(beneficiary),
+                grant_counter,
+                new_employee_account: company.employee_account(beneficiary, grant_id),
+                system_program: system_program::ID,
+            },
+            tokenvesting::instruction::MigrateEmployeeAccount {},
+        )
+    }
+
+    pub fn release_legacy_reserve_ix(&self, company: &Company) -> Instruction {
+        instruction(
+            tokenvesting::accounts::ReleaseLegacyReserve {
+                owner: company.owner,
+                vesting_account: company.vesting_account,
+            },
+            tokenvesting::instruction::ReleaseLegacyReserve {},
+        )
+    }
+}
+
+/// A grant in the layout used before `VESTING_VERSION`.
+pub struct LegacyGrant {
+    pub beneficiary: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub cliff_time: i64,
+    pub total_amount: u64,
+    pub total_withdrawn: u64,
+}
+
+pub fn employee_account_address(
+    vesting_account: Pubkey,
+    beneficiary: Pubkey,
+    grant_id: u64,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"employee_vesting",
+            beneficiary.as_ref(),
+            vesting_account.as_ref(),
+            &grant_id.to_le_bytes(),
+        ],
+        &tokenvesting::ID,
+    )
+    .0
+}
+
+/// Tranches of a milestone grant, none attested yet.
+pub fn milestones(amounts: &[u64]) -> Vec<Milestone> {
+    amounts
+        .iter()
+        .map(|amount| Milestone {
+            amount: *amount,
+            attested_at: None,
+        })
+        .collect()
+}
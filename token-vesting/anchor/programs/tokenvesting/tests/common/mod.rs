@@ -0,0 +1,502 @@
+//! Test bank for the integration tests, built on `solana-program-test`.
+//!
+//! `tokenvesting` is registered as a native processor, so the tests need no SBF
+//! build; SPL Token, Token-2022 and the associated token account program are the
+//! builds bundled with `solana-program-test`. `Bank` drives a `ProgramTestContext`
+//! on a runtime of its own, so that tests read as straight-line code, and keeps the
+//! keypair of every wallet and mint it creates to sign for them.
+#![allow(dead_code)]
+
+use std::{collections::HashMap, sync::Once};
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        entrypoint::ProgramResult,
+        instruction::{Instruction, InstructionError},
+        program_pack::Pack,
+        program_stubs::{set_syscall_stubs, SyscallStubs},
+        system_instruction,
+    },
+    system_program, AccountDeserialize, Discriminator, Event, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address_with_program_id, spl_associated_token_account,
+    },
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{ExtensionType, StateWithExtensions},
+    },
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use solana_program_test::{
+    processor,
+    tokio::runtime::{Builder, Runtime},
+    ProgramTest, ProgramTestContext,
+};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    signature::{Keypair, Signature, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use tokenvesting::{GrantParams, VestingSchedule};
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Start of every test's clock.
+pub const GENESIS: i64 = 1_700_000_000;
+
+/// Log line prefix of the events `Bank::events` reads: with `event-cpi` the payloads
+/// of the program's event self-CPIs, otherwise the program's `sol_log_data` output.
+#[cfg(not(feature = "event-cpi"))]
+const EVENT_LOG: &str = "Program log: Program data: ";
+#[cfg(feature = "event-cpi")]
+const EVENT_LOG: &str = "Program log: Event CPI: ";
+
+/// `tokenvesting`'s entrypoint as a native processor.
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    #[cfg(feature = "event-cpi")]
+    if let Some(event) = data.strip_prefix(&anchor_lang::event::EVENT_IX_TAG_LE) {
+        msg!("Event CPI: {}", BASE64.encode(event));
+    }
+    // Anchor's entrypoint ties the slice to the lifetime of the accounts in it.
+    let accounts = Box::leak(accounts.to_vec().into_boxed_slice());
+    tokenvesting::entry(program_id, accounts, data)
+}
+
+/// Writes `sol_log_data` output to the transaction log, which the syscall stubs of
+/// `solana-program-test` otherwise print to stdout for native programs. Every other
+/// syscall goes to those stubs.
+struct LogData(Box<dyn SyscallStubs>);
+
+impl SyscallStubs for LogData {
+    fn sol_log(&self, message: &str) {
+        self.0.sol_log(message)
+    }
+    fn sol_log_compute_units(&self) {
+        self.0.sol_log_compute_units()
+    }
+    fn sol_remaining_compute_units(&self) -> u64 {
+        self.0.sol_remaining_compute_units()
+    }
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.0
+            .sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_clock_sysvar(var_addr)
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_fees_sysvar(var_addr)
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_rent_sysvar(var_addr)
+    }
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_last_restart_slot(var_addr)
+    }
+    unsafe fn sol_memcpy(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.0.sol_memcpy(dst, src, n)
+    }
+    unsafe fn sol_memmove(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.0.sol_memmove(dst, src, n)
+    }
+    unsafe fn sol_memcmp(&self, s1: *const u8, s2: *const u8, n: usize, result: *mut i32) {
+        self.0.sol_memcmp(s1, s2, n, result)
+    }
+    unsafe fn sol_memset(&self, s: *mut u8, c: u8, n: usize) {
+        self.0.sol_memset(s, c, n)
+    }
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.0.sol_get_return_data()
+    }
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.0.sol_set_return_data(data)
+    }
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<String> = fields.iter().map(|field| BASE64.encode(field)).collect();
+        self.0
+            .sol_log(&format!("Program data: {}", fields.join(" ")))
+    }
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        self.0.sol_get_processed_sibling_instruction(index)
+    }
+    fn sol_get_stack_height(&self) -> u64 {
+        self.0.sol_get_stack_height()
+    }
+}
+
+/// A `ProgramTestContext` with the keypairs to sign for its wallets, and the
+/// results of the last transaction.
+pub struct Bank {
+    // Dropped before the runtime it was started on.
+    context: ProgramTestContext,
+    runtime: Runtime,
+    keypairs: HashMap<Pubkey, Keypair>,
+    signatures: Vec<Signature>,
+    clock: Clock,
+    pub payer: Pubkey,
+    pub logs: Vec<String>,
+    return_data: Option<(Pubkey, Vec<u8>)>,
+}
+
+impl Bank {
+    pub fn new() -> Self {
+        let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+        let mut program_test = ProgramTest::new(
+            "tokenvesting",
+            tokenvesting::ID,
+            processor!(process_instruction),
+        );
+        program_test.prefer_bpf(false);
+        let mut context = runtime.block_on(program_test.start_with_context());
+
+        // `start_with_context` has installed the stubs of `solana-program-test` by now,
+        // and no bank runs a program before it gets past this point.
+        static LOG_DATA: Once = Once::new();
+        LOG_DATA.call_once(|| {
+            struct Unset;
+            impl SyscallStubs for Unset {}
+            let stubs = set_syscall_stubs(Box::new(Unset));
+            set_syscall_stubs(Box::new(LogData(stubs)));
+        });
+
+        let clock = runtime
+            .block_on(context.banks_client.get_sysvar::<Clock>())
+            .unwrap();
+        let payer = context.payer.pubkey();
+        let mut bank = Self {
+            context,
+            runtime,
+            keypairs: HashMap::new(),
+            signatures: Vec::new(),
+            clock,
+            payer,
+            logs: Vec::new(),
+            return_data: None,
+        };
+        bank.warp_to(GENESIS);
+        bank
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<Account> {
+        let mut banks_client = self.context.banks_client.clone();
+        self.runtime
+            .block_on(banks_client.get_account(*key))
+            .unwrap()
+    }
+
+    pub fn set_account(&mut self, key: Pubkey, account: Account) {
+        self.context
+            .set_account(&key, &AccountSharedData::from(account));
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.account(key).map_or(0, |account| account.lamports)
+    }
+
+    pub fn airdrop(&mut self, key: Pubkey, lamports: u64) {
+        let mut account = self
+            .account(&key)
+            .unwrap_or_else(|| Account::new(0, 0, &system_program::ID));
+        account.lamports += lamports;
+        self.set_account(key, account);
+    }
+
+    /// A new address whose keypair signs for it from now on.
+    pub fn keypair(&mut self) -> Pubkey {
+        let keypair = Keypair::new();
+        let key = keypair.pubkey();
+        self.keypairs.insert(key, keypair);
+        key
+    }
+
+    /// A fresh system account with some SOL to pay rent.
+    pub fn funded_wallet(&mut self) -> Pubkey {
+        let wallet = self.keypair();
+        self.airdrop(wallet, 10 * LAMPORTS_PER_SOL);
+        wallet
+    }
+
+    pub fn anchor_account<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        let account = self
+            .account(key)
+            .unwrap_or_else(|| panic!("account {key} does not exist"));
+        T::try_deserialize(&mut account.data.as_slice()).expect("account does not deserialize")
+    }
+
+    pub fn warp_to(&mut self, unix_timestamp: i64) {
+        self.clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&self.clock);
+    }
+
+    pub fn warp_to_epoch(&mut self, epoch: u64) {
+        self.clock.epoch = epoch;
+        self.context.set_sysvar(&self.clock);
+    }
+
+    /// Moves to the next slot for a new blockhash, keeping the clock where it was.
+    fn next_slot(&mut self) {
+        self.clock.slot += 1;
+        self.context.warp_to_slot(self.clock.slot).unwrap();
+        self.context.set_sysvar(&self.clock);
+        self.signatures.clear();
+    }
+
+    /// Return data of the last transaction, decoded as `T`.
+    pub fn return_data<T: AnchorDeserialize>(&self) -> T {
+        let (program_id, data) = self.return_data.as_ref().expect("no return data");
+        assert_eq!(*program_id, tokenvesting::ID);
+        T::try_from_slice(data).expect("return data does not deserialize")
+    }
+
+    /// Events of type `E` emitted by the last transaction.
+    pub fn events<E: Event + Discriminator>(&self) -> Vec<E> {
+        self.logs
+            .iter()
+            .filter_map(|log| log.strip_prefix(EVENT_LOG))
+            .map(|data| BASE64.decode(data).expect("event data is not base64"))
+            .filter(|data| data.starts_with(&E::DISCRIMINATOR))
+            .map(|data| E::try_from_slice(&data[8..]).expect("event does not deserialize"))
+            .collect()
+    }
+
+    /// Runs `instructions` as one transaction paid for by `payer`. `signers` may
+    /// list more accounts than the instructions need signatures from.
+    pub fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[Pubkey],
+    ) -> std::result::Result<(), TransactionError> {
+        let mut transaction = Transaction::new_with_payer(instructions, Some(&self.payer));
+        let required = transaction.message.header.num_required_signatures as usize;
+        let mut keypairs = vec![&self.context.payer];
+        keypairs.extend(
+            signers
+                .iter()
+                .filter(|signer| transaction.message.account_keys[..required].contains(signer))
+                .filter(|signer| **signer != self.payer)
+                .map(|signer| {
+                    self.keypairs
+                        .get(signer)
+                        .unwrap_or_else(|| panic!("no keypair for {signer}"))
+                }),
+        );
+        transaction.sign(&keypairs, self.context.last_blockhash);
+        // An identical transaction in the same slot would be rejected as a duplicate.
+        if self.signatures.contains(&transaction.signatures[0]) {
+            self.next_slot();
+            return self.process(instructions, signers);
+        }
+        self.signatures.push(transaction.signatures[0]);
+
+        let mut banks_client = self.context.banks_client.clone();
+        let outcome = self
+            .runtime
+            .block_on(banks_client.process_transaction_with_metadata(transaction))
+            .unwrap();
+        (self.logs, self.return_data) = match outcome.metadata {
+            Some(metadata) => (
+                metadata.log_messages,
+                metadata
+                    .return_data
+                    .map(|return_data| (return_data.program_id, return_data.data)),
+            ),
+            None => (Vec::new(), None),
+        };
+        outcome.result
+    }
+
+    /// Runs a single instruction as its own transaction.
+    pub fn send(
+        &mut self,
+        instruction: Instruction,
+        signers: &[Pubkey],
+    ) -> std::result::Result<(), TransactionError> {
+        self.process(&[instruction], signers)
+    }
+
+    /// Creates a mint with `extensions` initialized by `init_extensions` before the mint itself.
+    pub fn create_mint_with_extensions(
+        &mut self,
+        token_program: Pubkey,
+        extensions: &[ExtensionType],
+        init_extensions: Vec<Instruction>,
+    ) -> Pubkey {
+        let mint = self.keypair();
+        let space = if extensions.is_empty() {
+            spl_token::state::Mint::LEN
+        } else {
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(extensions)
+                .unwrap()
+        };
+        let mut instructions = vec![system_instruction::create_account(
+            &self.payer,
+            &mint,
+            Rent::default().minimum_balance(space),
+            space as u64,
+            &token_program,
+        )];
+        instructions.extend(init_extensions.into_iter().map(|mut instruction| {
+            instruction.accounts[0].pubkey = mint;
+            instruction
+        }));
+        instructions.push(
+            spl_token_2022::instruction::initialize_mint2(
+                &token_program,
+                &mint,
+                &self.payer,
+                None,
+                6,
+            )
+            .unwrap(),
+        );
+        self.process(&instructions, &[mint]).unwrap();
+        mint
+    }
+    pub fn create_mint(&mut self, token_program: Pubkey) -> Pubkey {
+        self.create_mint_with_extensions(token_program, &[], Vec::new())
+    }
+
+    /// Associated token account of `wallet`, created on first use.
+    pub fn token_account(&mut self, token_program: Pubkey, mint: Pubkey, wallet: Pubkey) -> Pubkey {
+        let address = get_associated_token_address_with_program_id(&wallet, &mint, &token_program);
+        if self.account(&address).is_none() {
+            let instruction =
+                spl_associated_token_account::instruction::create_associated_token_account(
+                    &self.payer,
+                    &wallet,
+                    &mint,
+                    &token_program,
+                );
+            self.process(&[instruction], &[]).unwrap();
+        }
+        address
+    }
+
+    pub fn mint_to(
+        &mut self,
+        token_program: Pubkey,
+        mint: Pubkey,
+        destination: Pubkey,
+        amount: u64,
+    ) {
+        let instruction = spl_token_2022::instruction::mint_to(
+            &token_program,
+            &mint,
+            &destination,
+            &self.payer,
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.process(&[instruction], &[]).unwrap();
+    }
+
+    /// Writes an initialized token account straight into the ledger, for state no
+    /// instruction can produce any more.
+    pub fn set_token_account(
+        &mut self,
+        key: Pubkey,
+        token_program: Pubkey,
+        mint: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+    ) {
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        spl_token::state::Account::pack(
+            spl_token::state::Account {
+                mint,
+                owner: authority,
+                amount,
+                state: spl_token::state::AccountState::Initialized,
+                ..spl_token::state::Account::default()
+            },
+            &mut data,
+        )
+        .unwrap();
+        self.set_account(
+            key,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: token_program,
+                ..Account::default()
+            },
+        );
+    }
+
+    pub fn token_balance(&self, token_account: &Pubkey) -> u64 {
+        let account = self
+            .account(token_account)
+            .unwrap_or_else(|| panic!("token account {token_account} does not exist"));
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+}
+
+/// Asserts the transaction failed with `error`, a program or an Anchor framework error.
+#[track_caller]
+pub fn assert_error(
+    result: std::result::Result<(), TransactionError>,
+    error: impl Into<u32> + std::fmt::Debug + Copy,
+) {
+    match result {
+        Err(TransactionError::InstructionError(_, InstructionError::Custom(code)))
+            if code == error.into() => {}
+        other => panic!("expected {error:?}, got {other:?}"),
+    }
+}
+
+/// A linear grant over `duration` seconds from `start_time`.
+pub fn linear_grant(
+    grant_id: u64,
+    start_time: i64,
+    duration: i64,
+    cliff: i64,
+    total_amount: u64,
+) -> GrantParams {
+    GrantParams {
+        grant_id,
+        start_time,
+        end_time: start_time + duration,
+        cliff_time: start_time + cliff,
+        total_amount,
+        schedule: VestingSchedule::Linear,
+        milestones: Vec::new(),
+    }
+}
+
+pub fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: tokenvesting::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+// The instruction builders name each instruction's accounts as the default build
+// declares them; with `event-cpi` the instructions that emit events take two more.
+#[cfg(not(feature = "event-cpi"))]
+mod company;
+#[cfg(not(feature = "event-cpi"))]
+pub use company::*;
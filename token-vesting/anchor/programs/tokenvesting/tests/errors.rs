@@ -0,0 +1,911 @@
+//! Every `ErrorCode` the program returns, each triggered through the instruction that
+//! raises it.
+#![cfg(not(feature = "event-cpi"))]
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self, extension::ExtensionType, instruction as token_2022_instruction,
+    },
+};
+use common::*;
+use solana_sdk::transaction::TransactionError;
+use tokenvesting::{
+    error::ErrorCode, ClaimPermission, GrantEntry, GrantParams, ProposalAction, VestingSchedule,
+};
+
+const DAY: i64 = 24 * 60 * 60;
+const START: i64 = GENESIS + DAY;
+
+fn grant(grant_id: u64, total_amount: u64) -> GrantParams {
+    linear_grant(grant_id, START, 100 * DAY, 10 * DAY, total_amount)
+}
+
+fn create_grant(
+    bank: &mut Bank,
+    company: &Company,
+    beneficiary: Pubkey,
+    grant: GrantParams,
+) -> Result<(), TransactionError> {
+    bank.send(
+        company.create_grant_ix(beneficiary, grant),
+        &[company.owner],
+    )
+}
+
+#[test]
+fn grant_parameters_are_validated() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let beneficiary = bank.funded_wallet();
+    let mut check = |grant, error: ErrorCode| {
+        assert_error(create_grant(&mut bank, &company, beneficiary, grant), error)
+    };
+
+    check(
+        GrantParams {
+            end_time: START,
+            ..grant(0, 100)
+        },
+        ErrorCode::InvalidVestingPeriod,
+    );
+    check(
+        GrantParams {
+            cliff_time: START - 1,
+            ..grant(0, 100)
+        },
+        ErrorCode::CliffBeforeStart,
+    );
+    check(
+        GrantParams {
+            cliff_time: START + 101 * DAY,
+            ..grant(0, 100)
+        },
+        ErrorCode::CliffAfterEnd,
+    );
+    check(grant(0, 0), ErrorCode::InvalidAmount);
+    check(
+        GrantParams {
+            schedule: VestingSchedule::Stepped { period: 0 },
+            ..grant(0, 100)
+        },
+        ErrorCode::InvalidStepPeriod,
+    );
+    check(
+        GrantParams {
+            schedule: VestingSchedule::Stepped { period: 101 * DAY },
+            ..grant(0, 100)
+        },
+        ErrorCode::InvalidStepPeriod,
+    );
+    check(
+        GrantParams {
+            start_time: i64::MIN,
+            end_time: i64::MAX,
+            cliff_time: START,
+            schedule: VestingSchedule::Stepped { period: DAY },
+            ..grant(0, 100)
+        },
+        ErrorCode::InvalidVestingPeriod,
+    );
+    check(
+        GrantParams {
+            milestones: vec![100],
+            ..grant(0, 100)
+        },
+        ErrorCode::InvalidMilestones,
+    );
+    check(
+        GrantParams {
+            schedule: VestingSchedule::Milestone,
+            milestones: vec![40, 50],
+            ..grant(0, 100)
+        },
+        ErrorCode::InvalidMilestones,
+    );
+    check(
+        GrantParams {
+            schedule: VestingSchedule::Milestone,
+            milestones: vec![100, 0],
+            ..grant(0, 100)
+        },
+        ErrorCode::InvalidMilestones,
+    );
+    check(
+        GrantParams {
+            schedule: VestingSchedule::Milestone,
+            milestones: vec![u64::MAX, 1],
+            ..grant(0, 100)
+        },
+        ErrorCode::CalculationOverflow,
+    );
+    check(grant(1, 100), ErrorCode::InvalidGrantId);
+    check(grant(0, 1_001), ErrorCode::InsufficientTreasuryBalance);
+    assert!(bank
+        .account(&company.employee_account(beneficiary, 0))
+        .is_none());
+}
+
+#[test]
+fn treasury_commitments_cannot_overflow_or_be_withdrawn() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let beneficiary = bank.funded_wallet();
+    create_grant(&mut bank, &company, beneficiary, grant(0, 600)).unwrap();
+
+    assert_error(
+        create_grant(&mut bank, &company, beneficiary, grant(1, u64::MAX)),
+        ErrorCode::CalculationOverflow,
+    );
+    assert_error(
+        bank.send(
+            company.withdraw_unallocated_ix(company.owner_token_account, 401),
+            &[company.owner],
+        ),
+        ErrorCode::InsufficientUnallocatedBalance,
+    );
+    assert_error(
+        bank.send(
+            company.withdraw_unallocated_ix(company.owner_token_account, 0),
+            &[company.owner],
+        ),
+        ErrorCode::InvalidAmount,
+    );
+    assert_error(
+        bank.send(company.fund_treasury_ix(0), &[company.owner]),
+        ErrorCode::InvalidAmount,
+    );
+}
+
+#[test]
+fn claims_need_vested_unclaimed_tokens_from_the_beneficiarys_grants() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let beneficiary = bank.funded_wallet();
+    let other = bank.funded_wallet();
+    create_grant(&mut bank, &company, beneficiary, grant(0, 500)).unwrap();
+    create_grant(&mut bank, &company, other, grant(0, 500)).unwrap();
+
+    bank.warp_to(START + 10 * DAY - 1);
+    assert_error(
+        bank.send(company.claim_ix(beneficiary, 0), &[beneficiary]),
+        ErrorCode::ClaimNotAvailableYet,
+    );
+    bank.warp_to(START + 10 * DAY);
+    bank.send(company.claim_ix(beneficiary, 0), &[beneficiary])
+        .unwrap();
+    assert_error(
+        bank.send(company.claim_ix(beneficiary, 0), &[beneficiary]),
+        ErrorCode::NothingToClaim,
+    );
+
+    let mut foreign_grant = company.claim_all_ix(beneficiary, &[0]);
+    foreign_grant.accounts.last_mut().unwrap().pubkey = company.employee_account(other, 0);
+    assert_error(
+        bank.send(foreign_grant, &[beneficiary]),
+        ErrorCode::InvalidGrantAccount,
+    );
+
+    let mut read_only_grant = company.claim_all_ix(beneficiary, &[0]);
+    read_only_grant.accounts.last_mut().unwrap().is_writable = false;
+    assert_error(
+        bank.send(read_only_grant, &[beneficiary]),
+        ErrorCode::InvalidGrantAccount,
+    );
+
+    let mut missing_grant = company.claim_all_ix(beneficiary, &[0]);
+    missing_grant.accounts.pop();
+    assert_error(
+        bank.send(missing_grant, &[beneficiary]),
+        ErrorCode::InvalidGrantAccount,
+    );
+
+    assert_error(
+        bank.send(company.claim_all_ix(beneficiary, &[0]), &[beneficiary]),
+        ErrorCode::NothingToClaim,
+    );
+
+    let keeper = bank.funded_wallet();
+    bank.warp_to(START + 50 * DAY);
+    assert_error(
+        bank.send(company.crank_claim_ix(keeper, beneficiary, 0), &[keeper]),
+        ErrorCode::ClaimNotDelegated,
+    );
+    bank.send(
+        company.set_claim_delegate_ix(
+            beneficiary,
+            0,
+            ClaimPermission::Delegate { delegate: other },
+        ),
+        &[beneficiary],
+    )
+    .unwrap();
+    assert_error(
+        bank.send(company.crank_claim_ix(keeper, beneficiary, 0), &[keeper]),
+        ErrorCode::ClaimNotDelegated,
+    );
+}
+
+#[test]
+fn revoked_grants_cannot_be_revoked_or_attested_again() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let beneficiary = bank.funded_wallet();
+    create_grant(
+        &mut bank,
+        &company,
+        beneficiary,
+        GrantParams {
+            schedule: VestingSchedule::Milestone,
+            milestones: vec![100, 200],
+            ..grant(0, 300)
+        },
+    )
+    .unwrap();
+
+    bank.send(
+        company.revoke_ix(beneficiary, 0, company.owner_token_account),
+        &[company.owner],
+    )
+    .unwrap();
+    assert_error(
+        bank.send(
+            company.revoke_ix(beneficiary, 0, company.owner_token_account),
+            &[company.owner],
+        ),
+        ErrorCode::AlreadyRevoked,
+    );
+    assert_error(
+        bank.send(
+            company.attest_milestone_ix(beneficiary, 0, 0),
+            &[company.owner],
+        ),
+        ErrorCode::AlreadyRevoked,
+    );
+}
+
+#[test]
+fn milestones_are_attested_once_on_milestone_grants() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let beneficiary = bank.funded_wallet();
+    create_grant(&mut bank, &company, beneficiary, grant(0, 100)).unwrap();
+    create_grant(
+        &mut bank,
+        &company,
+        beneficiary,
+        GrantParams {
+            schedule: VestingSchedule::Milestone,
+            milestones: vec![100, 200],
+            ..grant(1, 300)
+        },
+    )
+    .unwrap();
+
+    assert_error(
+        bank.send(
+            company.attest_milestone_ix(beneficiary, 0, 0),
+            &[company.owner],
+        ),
+        ErrorCode::NotMilestoneGrant,
+    );
+    assert_error(
+        bank.send(
+            company.attest_milestone_ix(beneficiary, 1, 2),
+            &[company.owner],
+        ),
+        ErrorCode::InvalidMilestoneIndex,
+    );
+    bank.send(
+        company.attest_milestone_ix(beneficiary, 1, 1),
+        &[company.owner],
+    )
+    .unwrap();
+    assert_error(
+        bank.send(
+            company.attest_milestone_ix(beneficiary, 1, 1),
+            &[company.owner],
+        ),
+        ErrorCode::MilestoneAlreadyAttested,
+    );
+}
+
+#[test]
+fn accounts_close_only_once_settled_and_empty() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let beneficiary = bank.funded_wallet();
+    create_grant(&mut bank, &company, beneficiary, grant(0, 100)).unwrap();
+
+    bank.warp_to(START + 50 * DAY);
+    bank.send(company.claim_ix(beneficiary, 0), &[beneficiary])
+        .unwrap();
+    assert_error(
+        bank.send(
+            company.close_employee_account_ix(company.owner, beneficiary, 0),
+            &[company.owner],
+        ),
+        ErrorCode::GrantNotSettled,
+    );
+    assert_error(
+        bank.send(company.close_vesting_account_ix(&[]), &[company.owner]),
+        ErrorCode::GrantsStillOpen,
+    );
+
+    bank.warp_to(START + 100 * DAY);
+    bank.send(company.claim_ix(beneficiary, 0), &[beneficiary])
+        .unwrap();
+    bank.send(
+        company.close_employee_account_ix(company.owner, beneficiary, 0),
+        &[company.owner],
+    )
+    .unwrap();
+    assert_error(
+        bank.send(company.close_vesting_account_ix(&[]), &[company.owner]),
+        ErrorCode::TreasuryNotEmpty,
+    );
+}
+
+#[test]
+fn closing_a_vesting_account_waits_for_its_proposals_and_takes_only_its_own_leftovers() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 0, 0);
+    let other = Company::setup(&mut bank, spl_token::ID, 100, 100);
+    let beneficiary = bank.funded_wallet();
+    create_grant(&mut bank, &other, beneficiary, grant(0, 100)).unwrap();
+    let admins = [bank.funded_wallet(), bank.funded_wallet()];
+    bank.send(company.set_admins_ix(admins.to_vec(), 2), &[company.owner])
+        .unwrap();
+    let single_owner = ProposalAction::SetAdmins {
+        admins: Vec::new(),
+        threshold: 0,
+    };
+    bank.send(
+        company.create_proposal_ix(&bank, admins[0], single_owner),
+        &[admins[0]],
+    )
+    .unwrap();
+    assert_eq!(company.state(&bank).open_proposals, 1);
+
+    assert_error(
+        bank.send(company.close_vesting_account_ix(&[]), &[company.owner]),
+        ErrorCode::ProposalsStillOpen,
+    );
+    bank.send(company.cancel_proposal_ix(admins[0], 0), &[admins[0]])
+        .unwrap();
+    assert_eq!(company.state(&bank).open_proposals, 0);
+
+    for leftovers in [
+        vec![other.grant_counter(beneficiary)],
+        vec![company.vesting_account],
+    ] {
+        assert_error(
+            bank.send(
+                company.close_vesting_account_ix(&leftovers),
+                &[company.owner],
+            ),
+            ErrorCode::InvalidLeftoverAccount,
+        );
+    }
+    bank.send(company.close_vesting_account_ix(&[]), &[company.owner])
+        .unwrap();
+    assert!(bank.account(&company.vesting_account).is_none());
+}
+
+#[test]
+fn grants_move_only_to_a_different_beneficiary() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let beneficiary = bank.funded_wallet();
+    create_grant(&mut bank, &company, beneficiary, grant(0, 100)).unwrap();
+
+    assert_error(
+        bank.send(
+            company.transfer_beneficiary_ix(beneficiary, 0, beneficiary, 1),
+            &[company.owner, beneficiary],
+        ),
+        ErrorCode::SameBeneficiary,
+    );
+    assert_error(
+        bank.send(
+            company.recover_beneficiary_ix(beneficiary, 0, beneficiary, 1),
+            &[company.owner],
+        ),
+        ErrorCode::SameBeneficiary,
+    );
+}
+
+#[test]
+fn mints_that_break_vesting_are_rejected() {
+    let mut bank = Bank::new();
+    let owner = bank.funded_wallet();
+
+    let non_transferable = bank.create_mint_with_extensions(
+        spl_token_2022::ID,
+        &[ExtensionType::NonTransferable],
+        vec![token_2022_instruction::initialize_non_transferable_mint(
+            &spl_token_2022::ID,
+            &Pubkey::default(),
+        )
+        .unwrap()],
+    );
+    let company = Company::derive(
+        owner,
+        "Acme".to_string(),
+        non_transferable,
+        spl_token_2022::ID,
+    );
+    assert_error(
+        bank.send(company.create_vesting_account_ix(), &[owner]),
+        ErrorCode::NonTransferableMint,
+    );
+
+    let permanent_delegate = bank.create_mint_with_extensions(
+        spl_token_2022::ID,
+        &[ExtensionType::PermanentDelegate],
+        vec![token_2022_instruction::initialize_permanent_delegate(
+            &spl_token_2022::ID,
+            &Pubkey::default(),
+            &Pubkey::new_unique(),
+        )
+        .unwrap()],
+    );
+    let company = Company::derive(
+        owner,
+        "Acme".to_string(),
+        permanent_delegate,
+        spl_token_2022::ID,
+    );
+    assert_error(
+        bank.send(company.create_vesting_account_ix(), &[owner]),
+        ErrorCode::UnsupportedMintExtension,
+    );
+}
+
+#[test]
+fn admin_sets_are_validated() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let admin = Pubkey::new_unique();
+    let mut check = |admins: Vec<Pubkey>, threshold| {
+        assert_error(
+            bank.send(company.set_admins_ix(admins, threshold), &[company.owner]),
+            ErrorCode::InvalidAdminSet,
+        )
+    };
+
+    check(vec![admin], 0);
+    check(vec![admin], 2);
+    check(vec![admin, admin], 1);
+    check((0..11).map(|_| Pubkey::new_unique()).collect(), 1);
+}
+
+#[test]
+fn native_sol_accounts_stay_with_their_owner_and_apart_from_token_instructions() {
+    let mut bank = Bank::new();
+    let company = Company::setup_native(&mut bank, LAMPORTS_PER_SOL);
+    let admin = bank.funded_wallet();
+    assert_error(
+        bank.send(company.set_admins_ix(vec![admin], 1), &[company.owner]),
+        ErrorCode::NativeSolUnsupported,
+    );
+
+    // Nothing cranks SOL claims, so a delegate would have nothing to call.
+    let beneficiary = bank.funded_wallet();
+    bank.send(
+        company.create_sol_grant_ix(beneficiary, grant(0, 100)),
+        &[company.owner],
+    )
+    .unwrap();
+    assert_error(
+        bank.send(
+            company.set_claim_delegate_ix(beneficiary, 0, ClaimPermission::Permissionless),
+            &[beneficiary],
+        ),
+        ErrorCode::NativeSolUnsupported,
+    );
+
+    // Each side's treasury fails the other side's account checks.
+    assert_error(
+        bank.send(
+            company.create_grant_ix(beneficiary, grant(0, 100)),
+            &[company.owner],
+        ),
+        anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram,
+    );
+    let token_company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    assert_error(
+        bank.send(
+            token_company.fund_sol_treasury_ix(100),
+            &[token_company.owner],
+        ),
+        anchor_lang::error::ErrorCode::AccountNotSystemOwned,
+    );
+}
+
+#[test]
+fn single_owner_and_multisig_modes_exclude_each_other() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let beneficiary = bank.funded_wallet();
+    let admins = [bank.funded_wallet(), bank.funded_wallet()];
+    create_grant(&mut bank, &company, beneficiary, grant(0, 100)).unwrap();
+
+    let withdraw = ProposalAction::WithdrawUnallocated {
+        destination_token_account: company.owner_token_account,
+        amount: 100,
+    };
+    assert_error(
+        bank.send(
+            company.create_proposal_ix(&bank, admins[0], withdraw.clone()),
+            &[admins[0]],
+        ),
+        ErrorCode::NotMultisig,
+    );
+
+    bank.send(company.propose_owner_ix(admins[0]), &[company.owner])
+        .unwrap();
+    bank.send(company.set_admins_ix(admins.to_vec(), 2), &[company.owner])
+        .unwrap();
+    // A handover proposed before the admins took over can no longer complete.
+    assert_error(
+        bank.send(company.accept_owner_ix(admins[0]), &[admins[0]]),
+        ErrorCode::MultisigRequired,
+    );
+    let multisig_required = [
+        company.create_grant_ix(beneficiary, grant(1, 100)),
+        company.batch_ix(vec![GrantEntry {
+            beneficiary,
+            grant: grant(1, 100),
+        }]),
+        company.revoke_ix(beneficiary, 0, company.owner_token_account),
+        company.withdraw_unallocated_ix(company.owner_token_account, 100),
+        company.set_admins_ix(admins.to_vec(), 1),
+        company.recover_beneficiary_ix(beneficiary, 0, admins[0], 0),
+        company.attest_milestone_ix(beneficiary, 0, 0),
+        company.propose_owner_ix(admins[0]),
+    ];
+    for instruction in multisig_required {
+        assert_error(
+            bank.send(instruction, &[company.owner]),
+            ErrorCode::MultisigRequired,
+        );
+    }
+
+    // A proposal approved under the admins cannot run once the owner is back in charge.
+    bank.send(
+        company.create_proposal_ix(&bank, admins[0], withdraw),
+        &[admins[0]],
+    )
+    .unwrap();
+    bank.send(company.approve_proposal_ix(admins[1], 0), &[admins[1]])
+        .unwrap();
+    let single_owner = ProposalAction::SetAdmins {
+        admins: Vec::new(),
+        threshold: 0,
+    };
+    bank.send(
+        company.create_proposal_ix(&bank, admins[0], single_owner),
+        &[admins[0]],
+    )
+    .unwrap();
+    bank.send(company.approve_proposal_ix(admins[1], 1), &[admins[1]])
+        .unwrap();
+    bank.send(company.execute_set_admins_ix(admins[0], 1), &[])
+        .unwrap();
+    assert_error(
+        bank.send(
+            company.execute_withdraw_unallocated_ix(admins[0], 0, company.owner_token_account),
+            &[],
+        ),
+        ErrorCode::NotMultisig,
+    );
+}
+
+#[test]
+fn proposals_need_distinct_admin_approvals_and_a_matching_execution() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let admins = [bank.funded_wallet(), bank.funded_wallet()];
+    let stranger = bank.funded_wallet();
+    bank.send(company.set_admins_ix(admins.to_vec(), 2), &[company.owner])
+        .unwrap();
+
+    let withdraw = ProposalAction::WithdrawUnallocated {
+        destination_token_account: company.owner_token_account,
+        amount: 100,
+    };
+    assert_error(
+        bank.send(
+            company.create_proposal_ix(&bank, stranger, withdraw.clone()),
+            &[stranger],
+        ),
+        ErrorCode::NotAdmin,
+    );
+    bank.send(
+        company.create_proposal_ix(&bank, admins[0], withdraw),
+        &[admins[0]],
+    )
+    .unwrap();
+    assert_error(
+        bank.send(company.approve_proposal_ix(stranger, 0), &[stranger]),
+        ErrorCode::NotAdmin,
+    );
+    assert_error(
+        bank.send(company.approve_proposal_ix(admins[0], 0), &[admins[0]]),
+        ErrorCode::AlreadyApproved,
+    );
+    assert_error(
+        bank.send(
+            company.execute_withdraw_unallocated_ix(admins[0], 0, company.owner_token_account),
+            &[],
+        ),
+        ErrorCode::NotEnoughApprovals,
+    );
+
+    bank.send(company.approve_proposal_ix(admins[1], 0), &[admins[1]])
+        .unwrap();
+    assert_error(
+        bank.send(company.execute_set_admins_ix(admins[0], 0), &[]),
+        ErrorCode::ProposalActionMismatch,
+    );
+    assert_error(
+        bank.send(company.execute_set_owner_ix(admins[0], 0), &[]),
+        ErrorCode::ProposalActionMismatch,
+    );
+    let other_destination = bank.token_account(spl_token::ID, company.mint, stranger);
+    assert_error(
+        bank.send(
+            company.execute_withdraw_unallocated_ix(admins[0], 0, other_destination),
+            &[],
+        ),
+        ErrorCode::ProposalActionMismatch,
+    );
+    bank.send(
+        company.execute_withdraw_unallocated_ix(admins[0], 0, company.owner_token_account),
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn batches_are_rejected_as_a_whole() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let beneficiary = bank.funded_wallet();
+    let entry = |grant| GrantEntry { beneficiary, grant };
+
+    assert_error(
+        bank.send(company.batch_ix(Vec::new()), &[company.owner]),
+        ErrorCode::InvalidBatch,
+    );
+
+    let mut missing_accounts = company.batch_ix(vec![entry(grant(0, 100))]);
+    missing_accounts.accounts.pop();
+    assert_error(
+        bank.send(missing_accounts, &[company.owner]),
+        ErrorCode::InvalidBatch,
+    );
+
+    let mut swapped_accounts = company.batch_ix(vec![entry(grant(0, 100))]);
+    let last = swapped_accounts.accounts.len() - 1;
+    swapped_accounts.accounts.swap(last - 1, last);
+    assert_error(
+        bank.send(swapped_accounts, &[company.owner]),
+        ErrorCode::InvalidGrantAccount,
+    );
+
+    assert_error(
+        bank.send(
+            company.batch_ix(vec![entry(grant(0, 100)), entry(grant(0, 100))]),
+            &[company.owner],
+        ),
+        ErrorCode::InvalidGrantId,
+    );
+    assert_error(
+        bank.send(
+            company.batch_ix(vec![
+                entry(grant(0, 100)),
+                entry(GrantParams {
+                    cliff_time: 0,
+                    ..grant(1, 100)
+                }),
+            ]),
+            &[company.owner],
+        ),
+        ErrorCode::CliffBeforeStart,
+    );
+
+    create_grant(&mut bank, &company, beneficiary, grant(0, 100)).unwrap();
+    assert_error(
+        bank.send(
+            company.batch_ix(vec![entry(grant(0, 100))]),
+            &[company.owner],
+        ),
+        ErrorCode::GrantAlreadyExists,
+    );
+}
+
+#[test]
+fn owner_handover_needs_the_proposed_owner() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let new_owner = bank.funded_wallet();
+    let stranger = bank.funded_wallet();
+
+    assert_error(
+        bank.send(company.cancel_owner_transfer_ix(), &[company.owner]),
+        ErrorCode::NoPendingOwner,
+    );
+    assert_error(
+        bank.send(company.accept_owner_ix(new_owner), &[new_owner]),
+        ErrorCode::NotPendingOwner,
+    );
+    bank.send(company.propose_owner_ix(new_owner), &[company.owner])
+        .unwrap();
+    assert_error(
+        bank.send(company.accept_owner_ix(stranger), &[stranger]),
+        ErrorCode::NotPendingOwner,
+    );
+    bank.send(company.cancel_owner_transfer_ix(), &[company.owner])
+        .unwrap();
+    assert_error(
+        bank.send(company.accept_owner_ix(new_owner), &[new_owner]),
+        ErrorCode::NotPendingOwner,
+    );
+}
+
+#[test]
+fn paused_accounts_reject_claims_and_new_grants() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let beneficiary = bank.funded_wallet();
+    let admin = bank.funded_wallet();
+    create_grant(&mut bank, &company, beneficiary, grant(0, 100)).unwrap();
+    bank.send(
+        company.set_claim_delegate_ix(beneficiary, 0, ClaimPermission::Permissionless),
+        &[beneficiary],
+    )
+    .unwrap();
+    bank.send(company.set_paused_ix(true), &[company.owner])
+        .unwrap();
+    bank.warp_to(START + 50 * DAY);
+
+    let paused = [
+        company.create_grant_ix(beneficiary, grant(1, 100)),
+        company.batch_ix(vec![GrantEntry {
+            beneficiary,
+            grant: grant(1, 100),
+        }]),
+        company.claim_ix(beneficiary, 0),
+        company.claim_all_ix(beneficiary, &[0]),
+        company.crank_claim_ix(admin, beneficiary, 0),
+    ];
+    for instruction in paused {
+        assert_error(
+            bank.send(instruction, &[company.owner, beneficiary, admin]),
+            ErrorCode::VestingPaused,
+        );
+    }
+
+    // Grants approved by admins wait for the unpause too.
+    bank.send(company.set_admins_ix(vec![admin], 1), &[company.owner])
+        .unwrap();
+    let create_grant = ProposalAction::CreateGrant {
+        beneficiary,
+        grant: grant(1, 100),
+    };
+    bank.send(
+        company.create_proposal_ix(&bank, admin, create_grant),
+        &[admin],
+    )
+    .unwrap();
+    assert_error(
+        bank.send(
+            company.execute_create_grant_ix(admin, admin, 0, beneficiary, 1),
+            &[admin],
+        ),
+        ErrorCode::VestingPaused,
+    );
+}
+
+#[test]
+fn only_the_legacy_owner_migrates_only_legacy_grants() {
+    let mut bank = Bank::new();
+    let mint = bank.create_mint(spl_token::ID);
+    let owner = bank.funded_wallet();
+    let impostor = bank.funded_wallet();
+    let legacy = LegacyCompany::derive("Acme");
+    legacy.install(&mut bank, owner, mint, spl_token::ID, 0);
+
+    let company = Company::derive(impostor, "Acme".to_string(), mint, spl_token::ID);
+    assert_error(
+        bank.send(legacy.migrate_vesting_account_ix(&company), &[impostor]),
+        ErrorCode::Unauthorized,
+    );
+
+    // A vesting account that was never migrated from has no legacy grants to take over.
+    let company = Company::setup(&mut bank, spl_token::ID, 100, 100);
+    let beneficiary = bank.funded_wallet();
+    assert_error(
+        bank.send(
+            legacy.migrate_employee_account_ix(&bank, &company, beneficiary, beneficiary),
+            &[beneficiary],
+        ),
+        ErrorCode::NotMigrationSource,
+    );
+}
+
+#[test]
+fn legacy_treasury_stays_reserved_until_its_grants_migrate() {
+    let mut bank = Bank::new();
+    let mint = bank.create_mint(spl_token::ID);
+    let owner = bank.funded_wallet();
+    let beneficiary = bank.funded_wallet();
+    let legacy = LegacyCompany::derive("Acme");
+    legacy.install(&mut bank, owner, mint, spl_token::ID, 500_000);
+    legacy.install_grant(
+        &mut bank,
+        LegacyGrant {
+            beneficiary,
+            start_time: START,
+            end_time: START + 400 * DAY,
+            cliff_time: START + 100 * DAY,
+            total_amount: 450_000,
+            total_withdrawn: 50_000,
+        },
+    );
+    let company = Company::derive(owner, "Acme".to_string(), mint, spl_token::ID);
+    bank.send(legacy.migrate_vesting_account_ix(&company), &[owner])
+        .unwrap();
+    let owner_token_account = bank.token_account(spl_token::ID, mint, owner);
+    let new_beneficiary = bank.funded_wallet();
+
+    // Before the grant migrates, the owner can neither withdraw nor regrant its tokens.
+    assert_error(
+        bank.send(
+            company.withdraw_unallocated_ix(owner_token_account, 1),
+            &[owner],
+        ),
+        ErrorCode::InsufficientUnallocatedBalance,
+    );
+    assert_error(
+        bank.send(
+            company.create_grant_ix(new_beneficiary, linear_grant(0, START, DAY, 0, 1)),
+            &[owner],
+        ),
+        ErrorCode::InsufficientTreasuryBalance,
+    );
+    assert_error(
+        bank.send(company.close_vesting_account_ix(&[]), &[owner]),
+        ErrorCode::LegacyGrantsPending,
+    );
+    assert_error(
+        bank.send(legacy.release_legacy_reserve_ix(&company), &[owner]),
+        ErrorCode::LegacyGrantsPending,
+    );
+
+    // Anyone can migrate the grant; what it leaves of the reserve stays locked until the
+    // migration window is over.
+    let stranger = bank.funded_wallet();
+    bank.send(
+        legacy.migrate_employee_account_ix(&bank, &company, beneficiary, stranger),
+        &[stranger],
+    )
+    .unwrap();
+    assert_error(
+        bank.send(
+            company.withdraw_unallocated_ix(owner_token_account, 1),
+            &[owner],
+        ),
+        ErrorCode::InsufficientUnallocatedBalance,
+    );
+    bank.warp_to(company.state(&bank).legacy_reserve_expires_at - 1);
+    assert_error(
+        bank.send(legacy.release_legacy_reserve_ix(&company), &[owner]),
+        ErrorCode::LegacyGrantsPending,
+    );
+}
@@ -0,0 +1,56 @@
+//! Events in the `event-cpi` build, which travel as self-CPIs instead of log data.
+#![cfg(feature = "event-cpi")]
+
+mod common;
+
+use anchor_lang::{prelude::Pubkey, system_program};
+use anchor_spl::token::spl_token;
+use common::*;
+use tokenvesting::events::VestingAccountCreated;
+
+#[test]
+fn vesting_account_creation_is_reported_through_a_self_cpi() {
+    let mut bank = Bank::new();
+    let mint = bank.create_mint(spl_token::ID);
+    let owner = bank.funded_wallet();
+    let name = "Acme".to_string();
+    let (vesting_account, _) = Pubkey::find_program_address(
+        &[b"vesting", owner.as_ref(), name.as_bytes()],
+        &tokenvesting::ID,
+    );
+    let (treasury, _) = Pubkey::find_program_address(
+        &[b"vesting_treasury", owner.as_ref(), name.as_bytes()],
+        &tokenvesting::ID,
+    );
+    let (event_authority, _) =
+        Pubkey::find_program_address(&[b"__event_authority"], &tokenvesting::ID);
+
+    bank.send(
+        instruction(
+            tokenvesting::accounts::CreateVestingAccount {
+                signer: owner,
+                vesting_account,
+                mint,
+                treasury_token_account: treasury,
+                system_program: system_program::ID,
+                token_program: spl_token::ID,
+                event_authority,
+                program: tokenvesting::ID,
+            },
+            tokenvesting::instruction::CreateVestingAccount {
+                company_name: name.clone(),
+            },
+        ),
+        &[owner],
+    )
+    .unwrap();
+
+    let created = bank.events::<VestingAccountCreated>();
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].vesting_account, vesting_account);
+    assert_eq!(created[0].owner, owner);
+    assert_eq!(created[0].mint, mint);
+    assert_eq!(created[0].company_name, name);
+    // Nothing goes to the log, where truncation could drop it.
+    assert!(!bank.logs.iter().any(|log| log.contains("Program data:")));
+}
@@ -0,0 +1,1083 @@
+//! End-to-end flows against the in-process bank, with the clock warped across each
+//! grant's schedule.
+#![cfg(not(feature = "event-cpi"))]
+
+mod common;
+
+use anchor_lang::prelude::{Pubkey, Rent};
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{self, TransferFeeConfig},
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        },
+        state::Mint,
+    },
+};
+use common::*;
+use tokenvesting::{
+    error::ErrorCode,
+    events::{
+        BeneficiaryTransferred, GrantCreated, GrantRevoked, MilestoneAttested,
+        OwnerTransferAccepted, ProposalExecuted, TokensClaimed, TreasuryFunded,
+    },
+    ClaimPermission, GrantEntry, GrantParams, Proposal, ProposalAction, VestingSchedule,
+    VestingStatus,
+};
+
+const DAY: i64 = 24 * 60 * 60;
+const START: i64 = GENESIS + DAY;
+
+/// 400_000 tokens over 400 days with a 100 day cliff: 1_000 tokens a day.
+fn four_hundred_day_grant(grant_id: u64) -> GrantParams {
+    linear_grant(grant_id, START, 400 * DAY, 100 * DAY, 400_000)
+}
+
+fn claim(bank: &mut Bank, company: &Company, beneficiary: Pubkey, grant_id: u64) -> u64 {
+    bank.send(company.claim_ix(beneficiary, grant_id), &[beneficiary])
+        .unwrap();
+    let claimed = bank.events::<TokensClaimed>();
+    assert_eq!(claimed.len(), 1);
+    claimed[0].amount
+}
+
+fn status(
+    bank: &mut Bank,
+    company: &Company,
+    beneficiary: Pubkey,
+    grant_id: u64,
+    timestamp: i64,
+) -> VestingStatus {
+    bank.send(
+        company.get_vesting_status_ix(beneficiary, grant_id, timestamp),
+        &[],
+    )
+    .unwrap();
+    bank.return_data()
+}
+
+#[test]
+fn linear_grant_vests_across_cliff_middle_and_end() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 2_000_000, 1_000_000);
+    assert_eq!(bank.token_balance(&company.treasury), 1_000_000);
+    assert_eq!(company.state(&bank).total_deposited, 1_000_000);
+
+    let beneficiary = bank.funded_wallet();
+    company.create_grant(&mut bank, beneficiary, four_hundred_day_grant(0));
+    let created = bank.events::<GrantCreated>();
+    assert_eq!(created[0].beneficiary, beneficiary);
+    assert_eq!(created[0].total_amount, 400_000);
+    assert_eq!(company.state(&bank).committed_amount, 400_000);
+    assert_eq!(company.state(&bank).active_grants, 1);
+
+    bank.warp_to(START + 100 * DAY - 1);
+    assert_error(
+        bank.send(company.claim_ix(beneficiary, 0), &[beneficiary]),
+        ErrorCode::ClaimNotAvailableYet,
+    );
+
+    bank.warp_to(START + 100 * DAY);
+    assert_eq!(claim(&mut bank, &company, beneficiary, 0), 100_000);
+    assert_error(
+        bank.send(company.claim_ix(beneficiary, 0), &[beneficiary]),
+        ErrorCode::NothingToClaim,
+    );
+
+    bank.warp_to(START + 250 * DAY + 12 * 60 * 60);
+    assert_eq!(claim(&mut bank, &company, beneficiary, 0), 150_500);
+
+    // One second before the end the rounding remainder is still held back.
+    bank.warp_to(START + 400 * DAY - 1);
+    assert_eq!(claim(&mut bank, &company, beneficiary, 0), 149_499);
+    bank.warp_to(START + 400 * DAY);
+    assert_eq!(claim(&mut bank, &company, beneficiary, 0), 1);
+
+    let beneficiary_tokens = company.beneficiary_token_account(beneficiary);
+    assert_eq!(bank.token_balance(&beneficiary_tokens), 400_000);
+    assert_eq!(bank.token_balance(&company.treasury), 600_000);
+    assert_eq!(
+        company.grant(&bank, beneficiary, 0).total_withdrawn,
+        400_000
+    );
+    assert_eq!(company.state(&bank).committed_amount, 0);
+
+    bank.warp_to(START + 1_000 * DAY);
+    assert_error(
+        bank.send(company.claim_ix(beneficiary, 0), &[beneficiary]),
+        ErrorCode::NothingToClaim,
+    );
+
+    // Winding down returns the grant's rent to the owner who paid it.
+    let owner_lamports = bank.lamports(&company.owner);
+    let grant_rent = bank.lamports(&company.employee_account(beneficiary, 0));
+    bank.send(
+        company.close_employee_account_ix(company.owner, beneficiary, 0),
+        &[company.owner],
+    )
+    .unwrap();
+    assert!(bank
+        .account(&company.employee_account(beneficiary, 0))
+        .is_none());
+    assert_eq!(bank.lamports(&company.owner), owner_lamports + grant_rent);
+    assert_eq!(company.state(&bank).active_grants, 0);
+
+    bank.send(
+        company.withdraw_unallocated_ix(company.owner_token_account, 600_000),
+        &[company.owner],
+    )
+    .unwrap();
+    assert_eq!(bank.token_balance(&company.owner_token_account), 1_600_000);
+    // The grant counter goes along with the vesting account.
+    let grant_counter = company.grant_counter(beneficiary);
+    let owner_lamports = bank.lamports(&company.owner);
+    let rent = bank.lamports(&company.vesting_account)
+        + bank.lamports(&company.treasury)
+        + bank.lamports(&grant_counter);
+    bank.send(
+        company.close_vesting_account_ix(&[grant_counter]),
+        &[company.owner],
+    )
+    .unwrap();
+    assert!(bank.account(&company.vesting_account).is_none());
+    assert!(bank.account(&company.treasury).is_none());
+    assert!(bank.account(&grant_counter).is_none());
+    assert_eq!(bank.lamports(&company.owner), owner_lamports + rent);
+}
+
+#[test]
+fn vesting_status_reports_claimable_amount_and_next_unlock() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 400_000, 400_000);
+    let beneficiary = bank.funded_wallet();
+    company.create_grant(&mut bank, beneficiary, four_hundred_day_grant(0));
+
+    let before_cliff = status(&mut bank, &company, beneficiary, 0, START);
+    assert_eq!(before_cliff.vested_amount, 0);
+    assert_eq!(before_cliff.claimable_amount, 0);
+    assert_eq!(before_cliff.next_unlock_time, Some(START + 100 * DAY));
+
+    bank.warp_to(START + 150 * DAY);
+    claim(&mut bank, &company, beneficiary, 0);
+    let after_claim = status(&mut bank, &company, beneficiary, 0, START + 200 * DAY);
+    assert_eq!(after_claim.vested_amount, 200_000);
+    assert_eq!(after_claim.withdrawn_amount, 150_000);
+    assert_eq!(after_claim.claimable_amount, 50_000);
+    // 1_000 tokens a day: the next token vests 86.4 seconds later.
+    assert_eq!(after_claim.next_unlock_time, Some(START + 200 * DAY + 87));
+
+    let ended = status(&mut bank, &company, beneficiary, 0, START + 400 * DAY);
+    assert_eq!(ended.claimable_amount, 250_000);
+    assert_eq!(ended.next_unlock_time, None);
+}
+
+#[test]
+fn stepped_grant_unlocks_whole_periods() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_200, 1_200);
+    let beneficiary = bank.funded_wallet();
+    let period = 30 * DAY;
+    company.create_grant(
+        &mut bank,
+        beneficiary,
+        GrantParams {
+            schedule: VestingSchedule::Stepped { period },
+            ..linear_grant(0, START, 12 * period, 0, 1_200)
+        },
+    );
+
+    bank.warp_to(START + period - 1);
+    assert_error(
+        bank.send(company.claim_ix(beneficiary, 0), &[beneficiary]),
+        ErrorCode::NothingToClaim,
+    );
+    bank.warp_to(START + period);
+    assert_eq!(claim(&mut bank, &company, beneficiary, 0), 100);
+    bank.warp_to(START + 5 * period + period / 2);
+    assert_eq!(claim(&mut bank, &company, beneficiary, 0), 400);
+    bank.warp_to(START + 12 * period);
+    assert_eq!(claim(&mut bank, &company, beneficiary, 0), 700);
+}
+
+#[test]
+fn milestone_grant_pays_attested_tranches_after_its_floor() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 600, 600);
+    let beneficiary = bank.funded_wallet();
+    company.create_grant(
+        &mut bank,
+        beneficiary,
+        GrantParams {
+            schedule: VestingSchedule::Milestone,
+            milestones: vec![100, 200, 300],
+            ..linear_grant(0, START, 365 * DAY, 30 * DAY, 600)
+        },
+    );
+    assert_eq!(
+        company.grant(&bank, beneficiary, 0).milestones,
+        milestones(&[100, 200, 300])
+    );
+
+    bank.warp_to(START + DAY);
+    bank.send(
+        company.attest_milestone_ix(beneficiary, 0, 1),
+        &[company.owner],
+    )
+    .unwrap();
+    let attested = bank.events::<MilestoneAttested>();
+    assert_eq!((attested[0].milestone_index, attested[0].amount), (1, 200));
+    assert_eq!(attested[0].attested_at, START + DAY);
+
+    // Attested, but the floor has not passed yet.
+    assert_error(
+        bank.send(company.claim_ix(beneficiary, 0), &[beneficiary]),
+        ErrorCode::ClaimNotAvailableYet,
+    );
+    assert_eq!(
+        status(&mut bank, &company, beneficiary, 0, START + DAY).next_unlock_time,
+        Some(START + 30 * DAY)
+    );
+
+    bank.warp_to(START + 30 * DAY);
+    assert_eq!(claim(&mut bank, &company, beneficiary, 0), 200);
+
+    bank.warp_to(START + 400 * DAY);
+    assert_error(
+        bank.send(company.claim_ix(beneficiary, 0), &[beneficiary]),
+        ErrorCode::NothingToClaim,
+    );
+    bank.send(
+        company.attest_milestone_ix(beneficiary, 0, 0),
+        &[company.owner],
+    )
+    .unwrap();
+    bank.send(
+        company.attest_milestone_ix(beneficiary, 0, 2),
+        &[company.owner],
+    )
+    .unwrap();
+    assert_eq!(claim(&mut bank, &company, beneficiary, 0), 400);
+    assert!(company.grant(&bank, beneficiary, 0).is_settled().unwrap());
+}
+
+/// A Token-2022 mint that withholds 1% of every transfer.
+fn transfer_fee_mint(bank: &mut Bank) -> Pubkey {
+    let authority = bank.payer;
+    let fee_config = transfer_fee::instruction::initialize_transfer_fee_config(
+        &spl_token_2022::ID,
+        &Pubkey::default(),
+        Some(&authority),
+        Some(&authority),
+        100,
+        u64::MAX,
+    )
+    .unwrap();
+    bank.create_mint_with_extensions(
+        spl_token_2022::ID,
+        &[ExtensionType::TransferFeeConfig],
+        vec![fee_config],
+    )
+}
+
+#[test]
+fn transfer_fee_mint_reports_the_fee_of_each_claim() {
+    let mut bank = Bank::new();
+    let mint = transfer_fee_mint(&mut bank);
+    let company =
+        Company::setup_with_mint(&mut bank, spl_token_2022::ID, mint, 1_000_000, 1_000_000);
+
+    // The 1% fee on the deposit stays withheld in the treasury.
+    assert_eq!(bank.events::<TreasuryFunded>()[0].amount, 990_000);
+    assert_eq!(company.state(&bank).total_deposited, 990_000);
+
+    let beneficiary = bank.funded_wallet();
+    company.create_grant(&mut bank, beneficiary, four_hundred_day_grant(0));
+    bank.warp_to(START + 200 * DAY);
+    bank.send(company.claim_ix(beneficiary, 0), &[beneficiary])
+        .unwrap();
+    let claimed = &bank.events::<TokensClaimed>()[0];
+    assert_eq!((claimed.amount, claimed.transfer_fee), (200_000, 2_000));
+    assert_eq!(
+        bank.token_balance(&company.beneficiary_token_account(beneficiary)),
+        198_000
+    );
+
+    let grant = company.grant(&bank, beneficiary, 0);
+    assert_eq!(
+        (grant.total_withdrawn, grant.total_transfer_fees),
+        (200_000, 2_000)
+    );
+}
+
+#[test]
+fn transfer_fee_treasury_closes_once_its_withheld_fees_are_harvested() {
+    let mut bank = Bank::new();
+    let mint = transfer_fee_mint(&mut bank);
+    let company =
+        Company::setup_with_mint(&mut bank, spl_token_2022::ID, mint, 1_000_000, 1_000_000);
+    bank.send(
+        company.withdraw_unallocated_ix(company.owner_token_account, 990_000),
+        &[company.owner],
+    )
+    .unwrap();
+    assert_eq!(bank.token_balance(&company.treasury), 0);
+
+    // The fee withheld on the deposit moves to the mint before the treasury closes.
+    bank.send(company.close_vesting_account_ix(&[]), &[company.owner])
+        .unwrap();
+    assert!(bank.account(&company.treasury).is_none());
+    let mint_account = bank.account(&mint).unwrap();
+    let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+    let withheld_amount = mint_state
+        .get_extension::<TransferFeeConfig>()
+        .unwrap()
+        .withheld_amount;
+    assert_eq!(u64::from(withheld_amount), 10_000);
+}
+
+#[test]
+fn revoking_freezes_vesting_and_returns_the_unvested_remainder() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 400_000, 400_000);
+    let beneficiary = bank.funded_wallet();
+    company.create_grant(&mut bank, beneficiary, four_hundred_day_grant(0));
+
+    bank.warp_to(START + 150 * DAY);
+    claim(&mut bank, &company, beneficiary, 0);
+
+    bank.warp_to(START + 200 * DAY);
+    bank.send(
+        company.revoke_ix(beneficiary, 0, company.owner_token_account),
+        &[company.owner],
+    )
+    .unwrap();
+    let revoked = &bank.events::<GrantRevoked>()[0];
+    assert_eq!(
+        (revoked.revoked_at, revoked.returned_amount),
+        (START + 200 * DAY, 200_000)
+    );
+    assert_eq!(bank.token_balance(&company.owner_token_account), 200_000);
+    assert_eq!(company.state(&bank).committed_amount, 50_000);
+
+    // What vested before the revocation can still be claimed, nothing after it.
+    assert_eq!(
+        status(&mut bank, &company, beneficiary, 0, START + 150 * DAY).next_unlock_time,
+        Some(START + 150 * DAY + 87)
+    );
+    assert_eq!(
+        status(&mut bank, &company, beneficiary, 0, START + 200 * DAY).next_unlock_time,
+        None
+    );
+    assert_error(
+        bank.send(
+            company.close_employee_account_ix(company.owner, beneficiary, 0),
+            &[company.owner],
+        ),
+        ErrorCode::GrantNotSettled,
+    );
+    bank.warp_to(START + 400 * DAY);
+    assert_eq!(claim(&mut bank, &company, beneficiary, 0), 50_000);
+    assert_eq!(
+        status(&mut bank, &company, beneficiary, 0, START + 400 * DAY).next_unlock_time,
+        None
+    );
+    assert_error(
+        bank.send(company.claim_ix(beneficiary, 0), &[beneficiary]),
+        ErrorCode::NothingToClaim,
+    );
+
+    bank.send(
+        company.close_employee_account_ix(company.owner, beneficiary, 0),
+        &[company.owner],
+    )
+    .unwrap();
+    assert_eq!(company.state(&bank).committed_amount, 0);
+    assert_eq!(bank.token_balance(&company.treasury), 0);
+}
+
+#[test]
+fn claim_all_settles_every_grant_of_the_beneficiary_once() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 600_000, 600_000);
+    let beneficiary = bank.funded_wallet();
+    company.create_grant(&mut bank, beneficiary, four_hundred_day_grant(0));
+    company.create_grant(
+        &mut bank,
+        beneficiary,
+        linear_grant(1, START, 100 * DAY, 0, 200_000),
+    );
+
+    bank.warp_to(START + 200 * DAY);
+    bank.send(
+        company.claim_all_ix(beneficiary, &[0, 1, 0]),
+        &[beneficiary],
+    )
+    .unwrap();
+    let amounts: Vec<u64> = bank
+        .events::<TokensClaimed>()
+        .iter()
+        .map(|claimed| claimed.amount)
+        .collect();
+    assert_eq!(amounts, [200_000, 200_000]);
+    assert_eq!(
+        bank.token_balance(&company.beneficiary_token_account(beneficiary)),
+        400_000
+    );
+    assert_eq!(company.state(&bank).committed_amount, 200_000);
+
+    assert_error(
+        bank.send(company.claim_all_ix(beneficiary, &[0, 1]), &[beneficiary]),
+        ErrorCode::NothingToClaim,
+    );
+}
+
+#[test]
+fn crank_claims_follow_the_beneficiarys_claim_permission() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 400_000, 400_000);
+    let beneficiary = bank.funded_wallet();
+    let keeper = bank.funded_wallet();
+    let stranger = bank.funded_wallet();
+    company.create_grant(&mut bank, beneficiary, four_hundred_day_grant(0));
+    bank.warp_to(START + 100 * DAY);
+
+    assert_error(
+        bank.send(company.crank_claim_ix(keeper, beneficiary, 0), &[keeper]),
+        ErrorCode::ClaimNotDelegated,
+    );
+
+    bank.send(
+        company.set_claim_delegate_ix(
+            beneficiary,
+            0,
+            ClaimPermission::Delegate { delegate: keeper },
+        ),
+        &[beneficiary],
+    )
+    .unwrap();
+    assert_error(
+        bank.send(
+            company.crank_claim_ix(stranger, beneficiary, 0),
+            &[stranger],
+        ),
+        ErrorCode::ClaimNotDelegated,
+    );
+    // The keeper pays for the beneficiary's token account, the tokens go to the beneficiary.
+    bank.send(company.crank_claim_ix(keeper, beneficiary, 0), &[keeper])
+        .unwrap();
+    assert_eq!(
+        bank.token_balance(&company.beneficiary_token_account(beneficiary)),
+        100_000
+    );
+    assert_eq!(bank.events::<TokensClaimed>()[0].beneficiary, beneficiary);
+
+    bank.send(
+        company.set_claim_delegate_ix(beneficiary, 0, ClaimPermission::Permissionless),
+        &[beneficiary],
+    )
+    .unwrap();
+    bank.warp_to(START + 200 * DAY);
+    bank.send(
+        company.crank_claim_ix(stranger, beneficiary, 0),
+        &[stranger],
+    )
+    .unwrap();
+    assert_eq!(
+        bank.token_balance(&company.beneficiary_token_account(beneficiary)),
+        200_000
+    );
+}
+
+#[test]
+fn admin_proposals_run_once_enough_admins_approve() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000_000, 1_000_000);
+    let admins = [
+        bank.funded_wallet(),
+        bank.funded_wallet(),
+        bank.funded_wallet(),
+    ];
+    bank.send(company.set_admins_ix(admins.to_vec(), 2), &[company.owner])
+        .unwrap();
+
+    // Grants now go through proposals.
+    let beneficiary = bank.funded_wallet();
+    assert_error(
+        bank.send(
+            company.create_grant_ix(beneficiary, four_hundred_day_grant(0)),
+            &[company.owner],
+        ),
+        ErrorCode::MultisigRequired,
+    );
+
+    let create_grant = ProposalAction::CreateGrant {
+        beneficiary,
+        grant: four_hundred_day_grant(0),
+    };
+    bank.send(
+        company.create_proposal_ix(&bank, admins[0], create_grant),
+        &[admins[0]],
+    )
+    .unwrap();
+    let proposal: Proposal = bank.anchor_account(&company.proposal(0));
+    assert_eq!(proposal.approvals, [admins[0]]);
+    assert_eq!(company.state(&bank).open_proposals, 1);
+
+    let executor = bank.funded_wallet();
+    let execute = company.execute_create_grant_ix(executor, admins[0], 0, beneficiary, 0);
+    assert_error(
+        bank.send(execute.clone(), &[executor]),
+        ErrorCode::NotEnoughApprovals,
+    );
+
+    bank.send(company.approve_proposal_ix(admins[2], 0), &[admins[2]])
+        .unwrap();
+    bank.send(execute, &[executor]).unwrap();
+    assert_eq!(bank.events::<ProposalExecuted>()[0].proposal_id, 0);
+    assert!(bank.account(&company.proposal(0)).is_none());
+    assert_eq!(company.state(&bank).open_proposals, 0);
+    let grant = company.grant(&bank, beneficiary, 0);
+    assert_eq!((grant.total_amount, grant.payer), (400_000, executor));
+
+    // Revocations and withdrawals too.
+    bank.warp_to(START + 200 * DAY);
+    let revoke = ProposalAction::RevokeGrant {
+        employee_account: company.employee_account(beneficiary, 0),
+        destination_token_account: company.owner_token_account,
+    };
+    bank.send(
+        company.create_proposal_ix(&bank, admins[1], revoke),
+        &[admins[1]],
+    )
+    .unwrap();
+    bank.send(company.approve_proposal_ix(admins[0], 1), &[admins[0]])
+        .unwrap();
+    bank.send(
+        company.execute_revoke_grant_ix(
+            admins[1],
+            1,
+            company.employee_account(beneficiary, 0),
+            company.owner_token_account,
+        ),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(bank.token_balance(&company.owner_token_account), 200_000);
+
+    let withdraw = ProposalAction::WithdrawUnallocated {
+        destination_token_account: company.owner_token_account,
+        amount: 600_000,
+    };
+    bank.send(
+        company.create_proposal_ix(&bank, admins[2], withdraw),
+        &[admins[2]],
+    )
+    .unwrap();
+    bank.send(company.approve_proposal_ix(admins[1], 2), &[admins[1]])
+        .unwrap();
+    bank.send(
+        company.execute_withdraw_unallocated_ix(admins[2], 2, company.owner_token_account),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(bank.token_balance(&company.owner_token_account), 800_000);
+    assert_eq!(bank.token_balance(&company.treasury), 200_000);
+
+    // Handing control back to the owner is itself a proposal.
+    let single_owner = ProposalAction::SetAdmins {
+        admins: Vec::new(),
+        threshold: 0,
+    };
+    bank.send(
+        company.create_proposal_ix(&bank, admins[0], single_owner),
+        &[admins[0]],
+    )
+    .unwrap();
+    bank.send(company.approve_proposal_ix(admins[1], 3), &[admins[1]])
+        .unwrap();
+    bank.send(company.execute_set_admins_ix(admins[0], 3), &[])
+        .unwrap();
+    assert!(company.state(&bank).is_single_owner());
+    claim(&mut bank, &company, beneficiary, 0);
+}
+
+#[test]
+fn admins_replace_the_owner_through_a_proposal() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 0, 0);
+    let admins = [bank.funded_wallet(), bank.funded_wallet()];
+    let new_owner = bank.funded_wallet();
+    let pending_owner = bank.funded_wallet();
+    bank.send(company.propose_owner_ix(pending_owner), &[company.owner])
+        .unwrap();
+    bank.send(company.set_admins_ix(admins.to_vec(), 2), &[company.owner])
+        .unwrap();
+
+    bank.send(
+        company.create_proposal_ix(&bank, admins[0], ProposalAction::SetOwner { new_owner }),
+        &[admins[0]],
+    )
+    .unwrap();
+    bank.send(company.approve_proposal_ix(admins[1], 0), &[admins[1]])
+        .unwrap();
+    bank.send(company.execute_set_owner_ix(admins[0], 0), &[])
+        .unwrap();
+    let replaced = bank.events::<OwnerTransferAccepted>();
+    assert_eq!(replaced.len(), 1);
+    assert_eq!(
+        (replaced[0].previous_owner, replaced[0].new_owner),
+        (company.owner, new_owner)
+    );
+    let state = company.state(&bank);
+    assert_eq!((state.owner, state.pending_owner), (new_owner, None));
+    assert!(!state.is_single_owner());
+
+    // Pausing moves to the new owner with the role.
+    assert_error(
+        bank.send(company.set_paused_ix(true), &[company.owner]),
+        anchor_lang::error::ErrorCode::ConstraintHasOne,
+    );
+    let company = Company {
+        owner: new_owner,
+        ..company
+    };
+    bank.send(company.set_paused_ix(true), &[new_owner])
+        .unwrap();
+    assert!(company.state(&bank).paused);
+}
+
+#[test]
+fn admins_attest_milestones_through_proposals() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 600, 600);
+    let beneficiary = bank.funded_wallet();
+    company.create_grant(
+        &mut bank,
+        beneficiary,
+        GrantParams {
+            schedule: VestingSchedule::Milestone,
+            milestones: vec![100, 500],
+            ..linear_grant(0, START, 365 * DAY, 0, 600)
+        },
+    );
+    let admins = [bank.funded_wallet(), bank.funded_wallet()];
+    bank.send(company.set_admins_ix(admins.to_vec(), 2), &[company.owner])
+        .unwrap();
+
+    let employee_account = company.employee_account(beneficiary, 0);
+    let attest = ProposalAction::AttestMilestone {
+        employee_account,
+        milestone_index: 1,
+    };
+    bank.send(
+        company.create_proposal_ix(&bank, admins[0], attest),
+        &[admins[0]],
+    )
+    .unwrap();
+    bank.send(company.approve_proposal_ix(admins[1], 0), &[admins[1]])
+        .unwrap();
+    bank.warp_to(START + DAY);
+    bank.send(
+        company.execute_attest_milestone_ix(admins[0], 0, employee_account),
+        &[],
+    )
+    .unwrap();
+    let attested = bank.events::<MilestoneAttested>();
+    assert_eq!((attested[0].milestone_index, attested[0].amount), (1, 500));
+    assert_eq!(bank.events::<ProposalExecuted>()[0].proposal_id, 0);
+    assert_eq!(claim(&mut bank, &company, beneficiary, 0), 500);
+}
+
+#[test]
+fn cancelled_proposals_refund_the_proposer() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let admins = [bank.funded_wallet(), bank.funded_wallet()];
+    bank.send(company.set_admins_ix(admins.to_vec(), 2), &[company.owner])
+        .unwrap();
+
+    let proposer_lamports = bank.lamports(&admins[0]);
+    let withdraw = ProposalAction::WithdrawUnallocated {
+        destination_token_account: company.owner_token_account,
+        amount: 1_000,
+    };
+    bank.send(
+        company.create_proposal_ix(&bank, admins[0], withdraw),
+        &[admins[0]],
+    )
+    .unwrap();
+    assert!(bank.lamports(&admins[0]) < proposer_lamports);
+    bank.send(company.cancel_proposal_ix(admins[0], 0), &[admins[0]])
+        .unwrap();
+    assert_eq!(bank.lamports(&admins[0]), proposer_lamports);
+    assert!(bank.account(&company.proposal(0)).is_none());
+}
+
+#[test]
+fn batch_creates_every_grant_or_none() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000_000, 1_000_000);
+    let alice = bank.funded_wallet();
+    let bob = bank.funded_wallet();
+    let entry = |beneficiary, grant| GrantEntry { beneficiary, grant };
+
+    bank.send(
+        company.batch_ix(vec![
+            entry(alice, four_hundred_day_grant(0)),
+            entry(alice, linear_grant(1, START, 10 * DAY, 0, 1_000)),
+            entry(bob, four_hundred_day_grant(0)),
+        ]),
+        &[company.owner],
+    )
+    .unwrap();
+    assert_eq!(bank.events::<GrantCreated>().len(), 3);
+    assert_eq!(company.grant(&bank, alice, 1).total_amount, 1_000);
+    assert_eq!(company.grant(&bank, bob, 0).beneficiary, bob);
+    let state = company.state(&bank);
+    assert_eq!((state.committed_amount, state.active_grants), (801_000, 3));
+
+    // The second entry does not fit in the treasury, so the first is not created either.
+    let carol = bank.funded_wallet();
+    assert_error(
+        bank.send(
+            company.batch_ix(vec![
+                entry(carol, linear_grant(0, START, 10 * DAY, 0, 100_000)),
+                entry(bob, linear_grant(1, START, 10 * DAY, 0, 100_000)),
+            ]),
+            &[company.owner],
+        ),
+        ErrorCode::InsufficientTreasuryBalance,
+    );
+    assert!(bank.account(&company.employee_account(carol, 0)).is_none());
+    assert!(bank.account(&company.grant_counter(carol)).is_none());
+    assert_eq!(company.state(&bank).committed_amount, 801_000);
+
+    // Grants made one by one afterwards continue the batch's ids.
+    company.create_grant(&mut bank, alice, linear_grant(2, START, 10 * DAY, 0, 1_000));
+
+    // Lamports sent to a grant's addresses ahead of time do not block the batch.
+    bank.airdrop(company.grant_counter(carol), 1);
+    bank.airdrop(company.employee_account(carol, 0), 1);
+    bank.send(
+        company.batch_ix(vec![entry(
+            carol,
+            linear_grant(0, START, 10 * DAY, 0, 1_000),
+        )]),
+        &[company.owner],
+    )
+    .unwrap();
+    assert_eq!(company.grant(&bank, carol, 0).total_amount, 1_000);
+    let rent_exempt_minimum = Rent::default().minimum_balance(
+        bank.account(&company.grant_counter(carol))
+            .unwrap()
+            .data
+            .len(),
+    );
+    assert_eq!(
+        bank.lamports(&company.grant_counter(carol)),
+        rent_exempt_minimum
+    );
+}
+
+#[test]
+fn pausing_holds_claims_without_stopping_vesting() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 400_000, 400_000);
+    let beneficiary = bank.funded_wallet();
+    company.create_grant(&mut bank, beneficiary, four_hundred_day_grant(0));
+
+    bank.send(company.set_paused_ix(true), &[company.owner])
+        .unwrap();
+    assert!(company.state(&bank).paused);
+    bank.warp_to(START + 200 * DAY);
+    assert_error(
+        bank.send(company.claim_ix(beneficiary, 0), &[beneficiary]),
+        ErrorCode::VestingPaused,
+    );
+
+    bank.warp_to(START + 300 * DAY);
+    bank.send(company.set_paused_ix(false), &[company.owner])
+        .unwrap();
+    assert_eq!(claim(&mut bank, &company, beneficiary, 0), 300_000);
+}
+
+#[test]
+fn ownership_moves_only_once_the_new_owner_accepts() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 1_000, 1_000);
+    let new_owner = bank.funded_wallet();
+
+    bank.send(company.propose_owner_ix(new_owner), &[company.owner])
+        .unwrap();
+    assert_eq!(company.state(&bank).pending_owner, Some(new_owner));
+    assert_eq!(company.state(&bank).owner, company.owner);
+
+    bank.send(company.accept_owner_ix(new_owner), &[new_owner])
+        .unwrap();
+    let accepted = &bank.events::<OwnerTransferAccepted>()[0];
+    assert_eq!(
+        (accepted.previous_owner, accepted.new_owner),
+        (company.owner, new_owner)
+    );
+    let state = company.state(&bank);
+    assert_eq!((state.owner, state.pending_owner), (new_owner, None));
+
+    // The previous owner is locked out; the treasury keeps its original derivation.
+    assert_error(
+        bank.send(company.set_paused_ix(true), &[company.owner]),
+        anchor_lang::error::ErrorCode::ConstraintHasOne,
+    );
+    let handed_over = Company {
+        owner: new_owner,
+        ..Company::derive(
+            company.owner,
+            company.name.clone(),
+            company.mint,
+            company.token_program,
+        )
+    };
+    bank.send(handed_over.set_paused_ix(true), &[new_owner])
+        .unwrap();
+}
+
+#[test]
+fn grants_move_to_a_new_beneficiary_with_their_progress() {
+    let mut bank = Bank::new();
+    let company = Company::setup(&mut bank, spl_token::ID, 400_000, 400_000);
+    let beneficiary = bank.funded_wallet();
+    let new_wallet = bank.funded_wallet();
+    let recovered_wallet = bank.funded_wallet();
+    company.create_grant(&mut bank, beneficiary, four_hundred_day_grant(0));
+
+    bank.warp_to(START + 100 * DAY);
+    claim(&mut bank, &company, beneficiary, 0);
+    bank.send(
+        company.transfer_beneficiary_ix(beneficiary, 0, new_wallet, 0),
+        &[company.owner, beneficiary],
+    )
+    .unwrap();
+    assert_eq!(
+        bank.events::<BeneficiaryTransferred>()[0].new_beneficiary,
+        new_wallet
+    );
+    assert!(bank
+        .account(&company.employee_account(beneficiary, 0))
+        .is_none());
+    assert_eq!(company.grant(&bank, new_wallet, 0).total_withdrawn, 100_000);
+
+    // Recovery needs only the owner.
+    bank.send(
+        company.recover_beneficiary_ix(new_wallet, 0, recovered_wallet, 0),
+        &[company.owner],
+    )
+    .unwrap();
+    bank.warp_to(START + 200 * DAY);
+    assert_eq!(claim(&mut bank, &company, recovered_wallet, 0), 100_000);
+}
+
+#[test]
+fn legacy_vesting_accounts_and_grants_migrate() {
+    let mut bank = Bank::new();
+    let mint = bank.create_mint(spl_token::ID);
+    let owner = bank.funded_wallet();
+    let beneficiary = bank.funded_wallet();
+    let legacy = LegacyCompany::derive("Acme");
+    legacy.install(&mut bank, owner, mint, spl_token::ID, 500_000);
+    let legacy_grant = legacy.install_grant(
+        &mut bank,
+        LegacyGrant {
+            beneficiary,
+            start_time: START,
+            end_time: START + 400 * DAY,
+            cliff_time: START + 100 * DAY,
+            total_amount: 450_000,
+            total_withdrawn: 50_000,
+        },
+    );
+
+    let company = Company::derive(owner, "Acme".to_string(), mint, spl_token::ID);
+    let owner_lamports = bank.lamports(&owner);
+    let legacy_rent = bank.lamports(&legacy.vesting_account) + bank.lamports(&legacy.treasury);
+    bank.send(legacy.migrate_vesting_account_ix(&company), &[owner])
+        .unwrap();
+    assert!(bank.account(&legacy.vesting_account).is_none());
+    assert!(bank.account(&legacy.treasury).is_none());
+    assert_eq!(bank.token_balance(&company.treasury), 500_000);
+    let state = company.state(&bank);
+    assert_eq!(state.legacy_vesting_account, Some(legacy.vesting_account));
+    assert_eq!(state.total_deposited, 500_000);
+    // Everything the legacy treasury held stays reserved for the legacy grants.
+    assert_eq!(state.legacy_reserved, 500_000);
+    assert_eq!((state.committed_amount, state.active_grants), (500_000, 0));
+    let new_rent = bank.lamports(&company.vesting_account) + bank.lamports(&company.treasury);
+    assert_eq!(
+        bank.lamports(&owner),
+        owner_lamports + legacy_rent - new_rent
+    );
+
+    // The beneficiary migrates their own grant, which commits what is left of it out of
+    // the reserve.
+    let owner_lamports = bank.lamports(&owner);
+    let grant_rent = bank.lamports(&legacy_grant);
+    bank.send(
+        legacy.migrate_employee_account_ix(&bank, &company, beneficiary, beneficiary),
+        &[beneficiary],
+    )
+    .unwrap();
+    assert!(bank.account(&legacy_grant).is_none());
+    assert_eq!(bank.lamports(&owner), owner_lamports + grant_rent);
+    let grant = company.grant(&bank, beneficiary, 0);
+    assert_eq!(grant.vesting_account, company.vesting_account);
+    assert_eq!(grant.payer, beneficiary);
+    assert_eq!(
+        (grant.total_amount, grant.total_withdrawn),
+        (450_000, 50_000)
+    );
+    let state = company.state(&bank);
+    assert_eq!(state.legacy_reserved, 100_000);
+    assert_eq!((state.committed_amount, state.active_grants), (500_000, 1));
+
+    // New grants need new deposits and take the next id.
+    let owner_token_account = bank.token_account(spl_token::ID, mint, owner);
+    bank.mint_to(spl_token::ID, mint, owner_token_account, 100_000);
+    bank.send(company.fund_treasury_ix(100_000), &[owner])
+        .unwrap();
+    company.create_grant(
+        &mut bank,
+        beneficiary,
+        linear_grant(1, START, 100 * DAY, 0, 100_000),
+    );
+    assert_eq!(company.state(&bank).committed_amount, 600_000);
+
+    bank.warp_to(START + 200 * DAY);
+    assert_eq!(claim(&mut bank, &company, beneficiary, 0), 175_000);
+
+    // Once the migration window is over, the owner can take back what no legacy grant
+    // claimed.
+    bank.warp_to(company.state(&bank).legacy_reserve_expires_at);
+    bank.send(legacy.release_legacy_reserve_ix(&company), &[owner])
+        .unwrap();
+    let state = company.state(&bank);
+    assert_eq!(state.legacy_reserved, 0);
+    assert_eq!(state.committed_amount, 325_000);
+    bank.send(
+        company.withdraw_unallocated_ix(owner_token_account, 100_000),
+        &[owner],
+    )
+    .unwrap();
+    assert_eq!(bank.token_balance(&owner_token_account), 100_000);
+}
+
+#[test]
+fn native_sol_grants_vest_claim_and_revoke_like_token_grants() {
+    let mut bank = Bank::new();
+    let company = Company::setup_native(&mut bank, 10 * LAMPORTS_PER_SOL);
+    let rent_exempt_minimum = Rent::default().minimum_balance(0);
+    assert_eq!(
+        bank.lamports(&company.treasury),
+        10 * LAMPORTS_PER_SOL + rent_exempt_minimum
+    );
+    assert!(company.state(&bank).is_native_sol());
+
+    let alice = bank.funded_wallet();
+    let bob = bank.funded_wallet();
+    let grant =
+        |grant_id| linear_grant(grant_id, START, 400 * DAY, 100 * DAY, 4 * LAMPORTS_PER_SOL);
+    bank.send(
+        company.create_sol_grant_ix(alice, grant(0)),
+        &[company.owner],
+    )
+    .unwrap();
+    bank.send(company.create_sol_grant_ix(bob, grant(0)), &[company.owner])
+        .unwrap();
+    assert_error(
+        bank.send(
+            company.create_sol_grant_ix(alice, grant(1)),
+            &[company.owner],
+        ),
+        ErrorCode::InsufficientTreasuryBalance,
+    );
+
+    bank.warp_to(START + 100 * DAY - 1);
+    assert_error(
+        bank.send(company.claim_sol_ix(alice, 0), &[alice]),
+        ErrorCode::ClaimNotAvailableYet,
+    );
+
+    bank.warp_to(START + 200 * DAY);
+    let alice_lamports = bank.lamports(&alice);
+    bank.send(company.claim_sol_ix(alice, 0), &[alice]).unwrap();
+    assert_eq!(
+        bank.events::<TokensClaimed>()[0].amount,
+        2 * LAMPORTS_PER_SOL
+    );
+    assert_eq!(bank.lamports(&alice), alice_lamports + 2 * LAMPORTS_PER_SOL);
+
+    let owner_lamports = bank.lamports(&company.owner);
+    bank.send(
+        company.revoke_sol_ix(bob, 0, company.owner),
+        &[company.owner],
+    )
+    .unwrap();
+    assert_eq!(
+        bank.events::<GrantRevoked>()[0].returned_amount,
+        2 * LAMPORTS_PER_SOL
+    );
+    assert_eq!(
+        bank.lamports(&company.owner),
+        owner_lamports + 2 * LAMPORTS_PER_SOL
+    );
+
+    bank.warp_to(START + 400 * DAY);
+    bank.send(company.claim_sol_ix(alice, 0), &[alice]).unwrap();
+    bank.send(company.claim_sol_ix(bob, 0), &[bob]).unwrap();
+    assert_eq!(
+        bank.events::<TokensClaimed>()[0].amount,
+        2 * LAMPORTS_PER_SOL
+    );
+    assert_eq!(company.state(&bank).committed_amount, 0);
+
+    for beneficiary in [alice, bob] {
+        bank.send(
+            company.close_employee_account_ix(company.owner, beneficiary, 0),
+            &[company.owner],
+        )
+        .unwrap();
+    }
+    assert_error(
+        bank.send(company.close_sol_vesting_account_ix(&[]), &[company.owner]),
+        ErrorCode::TreasuryNotEmpty,
+    );
+    assert_error(
+        bank.send(
+            company.withdraw_unallocated_sol_ix(company.owner, 2 * LAMPORTS_PER_SOL + 1),
+            &[company.owner],
+        ),
+        ErrorCode::InsufficientUnallocatedBalance,
+    );
+    bank.send(
+        company.withdraw_unallocated_sol_ix(company.owner, 2 * LAMPORTS_PER_SOL),
+        &[company.owner],
+    )
+    .unwrap();
+
+    let owner_lamports = bank.lamports(&company.owner);
+    let grant_counters = [company.grant_counter(alice), company.grant_counter(bob)];
+    let rent = bank.lamports(&company.vesting_account)
+        + grant_counters
+            .iter()
+            .map(|grant_counter| bank.lamports(grant_counter))
+            .sum::<u64>();
+    bank.send(
+        company.close_sol_vesting_account_ix(&grant_counters),
+        &[company.owner],
+    )
+    .unwrap();
+    assert!(bank.account(&company.treasury).is_none());
+    assert_eq!(
+        bank.lamports(&company.owner),
+        owner_lamports + rent + rent_exempt_minimum
+    );
+}
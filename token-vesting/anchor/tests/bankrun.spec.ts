@@ -7,6 +7,7 @@ import { BankrunProvider } from "anchor-bankrun";
 import { Tokenvesting } from "../target/types/tokenvesting";
 
 import {
+  createAssociatedTokenAccount,
   createMint,
   mintTo,
 } from "spl-token-bankrun";
@@ -69,17 +70,17 @@ describe("vesting Smart Contract Tests", () => {
         program2 = new anchor.Program<Tokenvesting>(IDL as Tokenvesting, beneficiaryProvider);
 
         [vestingAccountKey] = PublicKey.findProgramAddressSync(
-            [Buffer.from(companyName)],
+            [Buffer.from("vesting"), employer.publicKey.toBuffer(), Buffer.from(companyName)],
             program.programId
         );
 
         [treasuryTokenAccount] = PublicKey.findProgramAddressSync(
-            [Buffer.from("vesting_treasury"), Buffer.from(companyName)],
+            [Buffer.from("vesting_treasury"), employer.publicKey.toBuffer(), Buffer.from(companyName)],
             program.programId
         );
 
         [employeeAccount] = PublicKey.findProgramAddressSync(
-            [Buffer.from("employee_vesting"), beneficiary.publicKey.toBuffer(), vestingAccountKey.toBuffer()],
+            [Buffer.from("employee_vesting"), beneficiary.publicKey.toBuffer(), vestingAccountKey.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
             program.programId
         );
     });
@@ -100,21 +101,39 @@ describe("vesting Smart Contract Tests", () => {
     });
     it("Should fund the treasury token account", async () => {
         const amount = 10 * LAMPORTS_PER_SOL;
-        
+
+        const employerTokenAccount = await createAssociatedTokenAccount(
+            // @ts-ignore
+            banksClient,
+            employer,
+            mint,
+            employer.publicKey,
+        )
+
         const mintTx = await mintTo(
             // @ts-ignore
             banksClient,
             employer,
             mint,
-            treasuryTokenAccount,
+            employerTokenAccount,
             employer,
             amount,
         )
         console.log("Mint Tx: ", mintTx)
+
+        const fundTx = await program.methods.fundTreasury(new anchor.BN(amount)).accounts({
+            vestingAccount: vestingAccountKey,
+            ownerTokenAccount: employerTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+        }).rpc({ commitment: "confirmed" })
+
+        const vestingAccountData = await program.account.vestingAccount.fetch(vestingAccountKey)
+        expect(vestingAccountData.totalDeposited.toNumber()).toEqual(amount)
+        console.log("Fund Treasury: ", fundTx)
     })
 
     it("Should create employee vesting account", async () => {
-        const tx2 = await program.methods.createEmployeeAccount(new anchor.BN(0), new anchor.BN(100), new anchor.BN(100), new anchor.BN(10) ).accounts({
+        const tx2 = await program.methods.createEmployeeAccount(new anchor.BN(0), new anchor.BN(0), new anchor.BN(100), new anchor.BN(100), new anchor.BN(10), { linear: {} }, []).accounts({
             beneficiary: beneficiary.publicKey,
             vestingAccount: vestingAccountKey,
         }).rpc({ commitment: "confirmed" , skipPreflight: true})
@@ -138,7 +157,8 @@ describe("vesting Smart Contract Tests", () => {
             )
         );
 
-        const tx3 = await program2.methods.claimTokens(companyName).accounts({
+        const tx3 = await program2.methods.claimTokens().accounts({
+            employeeAccount: employeeAccount,
             tokenProgram: TOKEN_PROGRAM_ID,
         }).rpc({ commitment: "confirmed" })
 
@@ -91,14 +91,23 @@ export function useTokenvestingProgramAccount({
 
   const createEmployeeVesting = useMutation<string, Error, CreateEmployeeArgs>({
     mutationKey: ["employeeAccount", "create", { cluster }],
-    mutationFn: ({startTime,endTime,cliffTime,totalAmount,beneficiary}) =>
-      program.methods
-        .createEmployeeAccount(new BN(startTime), new BN(endTime), new BN(cliffTime), new BN(totalAmount))
+    mutationFn: async ({startTime,endTime,cliffTime,totalAmount,beneficiary}) => {
+      const beneficiaryKey = new PublicKey(beneficiary);
+      // New grants must take the beneficiary's next id, kept by their grant counter.
+      const [grantCounter] = PublicKey.findProgramAddressSync(
+        [new TextEncoder().encode("grant_counter"), account.toBuffer(), beneficiaryKey.toBuffer()],
+        program.programId,
+      );
+      const counter = await program.account.grantCounter.fetchNullable(grantCounter);
+      const grantId = counter?.grantCount ?? new BN(0);
+      return program.methods
+        .createEmployeeAccount(grantId, new BN(startTime), new BN(endTime), new BN(cliffTime), new BN(totalAmount), { linear: {} }, [])
         .accounts({
           vestingAccount: account,
-          beneficiary: new PublicKey(beneficiary)
+          beneficiary: beneficiaryKey
         })
-        .rpc(),
+        .rpc();
+    },
     onSuccess: (signature) => {
       transactionToast(signature);
       return accounts.refetch();
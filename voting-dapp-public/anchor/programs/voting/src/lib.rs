@@ -1,8 +1,17 @@
 #![allow(clippy::result_large_err)]
 
 use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_fee::TransferFeeAmount, transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions},
+};
+use anchor_spl::token_interface::{
+    self, CloseAccount, HarvestWithheldTokensToMint, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 
 pub const ANCHOR_DISCRIMINATOR: usize = 8;
+// Approval and ranked-choice ballots name candidates by index, so those polls have a bounded candidate list.
+pub const MAX_BALLOT_CANDIDATES: usize = 16;
 
 declare_id!("2JxqishUPxmwTWAk7rJGMEq4KQy8fMD7aKzdp4wqRTmn");
 
@@ -16,43 +25,363 @@ pub mod voting {
         poll_description: String,
         poll_start_time: u64,
         poll_end_time: u64,
+        allow_self_nomination: bool,
+        poll_type: PollType,
     ) -> Result<()> {
+        require!(poll_end_time >= poll_start_time, ErrorCode::InvalidPollWindow);
+        require!(
+            poll_type == PollType::SingleChoice || ctx.accounts.governance_mint.is_none(),
+            ErrorCode::WeightedBallotsUnsupported
+        );
+        if let Some(governance_mint) = &ctx.accounts.governance_mint {
+            require!(!has_transfer_hook(&governance_mint.to_account_info())?, ErrorCode::TransferHookUnsupported);
+        }
+
         let poll = &mut ctx.accounts.poll;
+        poll.authority = ctx.accounts.signer.key();
         poll.poll_id = poll_id;
         poll.poll_description = poll_description;
         poll.poll_start_time = poll_start_time;
         poll.poll_end_time = poll_end_time;
         poll.candidate_amount = 0;
+        poll.allow_self_nomination = allow_self_nomination;
+        // Polls created with a governance mint are token-weighted and take votes through `vote_weighted`.
+        poll.governance_mint = ctx.accounts.governance_mint.as_ref().map(|mint| mint.key());
+        poll.poll_type = poll_type;
+        poll.ballot_count = 0;
         Ok(())
     }
 
     pub fn initialize_candidate(ctx: Context<InitializeCandidate>, candidate_name: String, poll_id: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp as u64;
+        require!(now < ctx.accounts.poll.poll_start_time, ErrorCode::CandidateRegistrationClosed);
+
         let candidate = &mut ctx.accounts.candidate;
         candidate.candidate_name = candidate_name;
         candidate.poll_id = poll_id;
         candidate.candidate_votes = 0;
+        candidate.approved = true;
         let poll = &mut ctx.accounts.poll;
+        require!(poll.has_room_for_candidate(), ErrorCode::CandidateLimitReached);
+        candidate.candidate_index = poll.candidate_amount;
         
         poll.candidate_amount += 1;
 
         Ok(())
     }
 
-    pub fn vote(ctx: Context<Vote>, _candidate_name: String, _poll_id: u64) -> Result<()> {
-        
+    // Anyone can put a candidate forward, but it can't receive votes until the authority approves it.
+    pub fn nominate_candidate(ctx: Context<NominateCandidate>, candidate_name: String, poll_id: u64) -> Result<()> {
+        let poll = &ctx.accounts.poll;
+        require!(poll.allow_self_nomination, ErrorCode::SelfNominationDisabled);
+        let now = Clock::get()?.unix_timestamp as u64;
+        require!(now < poll.poll_start_time, ErrorCode::CandidateRegistrationClosed);
+
+        let candidate = &mut ctx.accounts.candidate;
+        candidate.candidate_name = candidate_name;
+        candidate.poll_id = poll_id;
+        candidate.candidate_votes = 0;
+        candidate.approved = false;
+
+        Ok(())
+    }
+
+    pub fn approve_candidate(ctx: Context<ApproveCandidate>, _candidate_name: String, _poll_id: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp as u64;
+        require!(now < ctx.accounts.poll.poll_start_time, ErrorCode::CandidateRegistrationClosed);
+
+        let candidate = &mut ctx.accounts.candidate;
+        require!(!candidate.approved, ErrorCode::CandidateAlreadyApproved);
+        candidate.approved = true;
+        let poll = &mut ctx.accounts.poll;
+        require!(poll.has_room_for_candidate(), ErrorCode::CandidateLimitReached);
+        candidate.candidate_index = poll.candidate_amount;
+
+        poll.candidate_amount += 1;
+
+        Ok(())
+    }
+
+    pub fn vote(ctx: Context<Vote>, _candidate_name: String, poll_id: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp as u64;
+        let poll = &ctx.accounts.poll;
+        require!(now >= poll.poll_start_time, ErrorCode::PollNotStarted);
+        require!(now <= poll.poll_end_time, ErrorCode::PollEnded);
+        require!(poll.poll_type == PollType::SingleChoice, ErrorCode::WrongPollType);
+        require!(poll.governance_mint.is_none(), ErrorCode::TokenWeightedPoll);
+
         let candidate = &mut ctx.accounts.candidate;
+        require!(candidate.approved, ErrorCode::CandidateNotApproved);
         candidate.candidate_votes += 1;
 
+        let voter_receipt = &mut ctx.accounts.voter_receipt;
+        voter_receipt.poll_id = poll_id;
+        voter_receipt.voter = ctx.accounts.signer.key();
+        voter_receipt.candidate = candidate.key();
+        voter_receipt.voted_at = now;
+        voter_receipt.weight = 1;
+
+
+
+        Ok(())
+    }
+
+    // The voter's whole governance balance is locked in escrow until the poll ends, so the same tokens can't be moved to another wallet and vote again.
+    pub fn vote_weighted(ctx: Context<VoteWeighted>, _candidate_name: String, poll_id: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp as u64;
+        let poll = &ctx.accounts.poll;
+        require!(now >= poll.poll_start_time, ErrorCode::PollNotStarted);
+        require!(now <= poll.poll_end_time, ErrorCode::PollEnded);
+        require!(poll.poll_type == PollType::SingleChoice, ErrorCode::WrongPollType);
+        require!(ctx.accounts.candidate.approved, ErrorCode::CandidateNotApproved);
+
+        let amount = ctx.accounts.voter_token_account.amount;
+        require!(amount > 0, ErrorCode::NoVotingPower);
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.voter_token_account.to_account_info(),
+                    mint: ctx.accounts.governance_mint.to_account_info(),
+                    to: ctx.accounts.vote_escrow.to_account_info(),
+                    authority: ctx.accounts.signer.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.governance_mint.decimals,
+        )?;
+
+        // Mints with a transfer fee deliver less than was sent; only what reached the escrow counts.
+        ctx.accounts.vote_escrow.reload()?;
+        let weight = ctx.accounts.vote_escrow.amount;
+
+        let candidate = &mut ctx.accounts.candidate;
+        candidate.candidate_votes += weight;
+
+        let voter_receipt = &mut ctx.accounts.voter_receipt;
+        voter_receipt.poll_id = poll_id;
+        voter_receipt.voter = ctx.accounts.signer.key();
+        voter_receipt.candidate = candidate.key();
+        voter_receipt.voted_at = now;
+        voter_receipt.weight = weight;
 
+        Ok(())
+    }
+
+    pub fn withdraw_vote_tokens(ctx: Context<WithdrawVoteTokens>, poll_id: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp as u64;
+        require!(now > ctx.accounts.poll.poll_end_time, ErrorCode::PollNotEnded);
+
+        // The poll account owns every escrow of the poll.
+        let poll_id_bytes = poll_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[poll_id_bytes.as_ref(), &[ctx.bumps.poll]]];
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vote_escrow.to_account_info(),
+                    mint: ctx.accounts.governance_mint.to_account_info(),
+                    to: ctx.accounts.voter_token_account.to_account_info(),
+                    authority: ctx.accounts.poll.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            ctx.accounts.vote_escrow.amount,
+            ctx.accounts.governance_mint.decimals,
+        )?;
+
+        // The fee withheld when the tokens came in stays in the escrow, and Token-2022 only closes it once that
+        // fee is harvested to the mint.
+        if withheld_transfer_fees(&ctx.accounts.vote_escrow.to_account_info())? > 0 {
+            token_interface::harvest_withheld_tokens_to_mint(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    HarvestWithheldTokensToMint {
+                        token_program_id: ctx.accounts.token_program.to_account_info(),
+                        mint: ctx.accounts.governance_mint.to_account_info(),
+                    },
+                ),
+                vec![ctx.accounts.vote_escrow.to_account_info()],
+            )?;
+        }
+
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.vote_escrow.to_account_info(),
+                destination: ctx.accounts.signer.to_account_info(),
+                authority: ctx.accounts.poll.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        Ok(())
+    }
+
+    // Approval ballots list any subset of candidates and are counted straight away; the remaining accounts are
+    // those candidates, in ballot order. Ranked ballots list candidates by preference and are only stored until
+    // the tally.
+    pub fn cast_ballot<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CastBallot<'info>>,
+        poll_id: u64,
+        choices: Vec<u8>,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp as u64;
+        let poll = &mut ctx.accounts.poll;
+        require!(now >= poll.poll_start_time, ErrorCode::PollNotStarted);
+        require!(now <= poll.poll_end_time, ErrorCode::PollEnded);
+        require!(poll.poll_type != PollType::SingleChoice, ErrorCode::WrongPollType);
+        validate_choices(&choices, poll.candidate_amount)?;
+
+        if poll.poll_type == PollType::Approval {
+            require_eq!(ctx.remaining_accounts.len(), choices.len(), ErrorCode::BallotCandidateMismatch);
+            for (candidate_info, &choice) in ctx.remaining_accounts.iter().zip(&choices) {
+                let mut candidate = Account::<Candidate>::try_from(candidate_info)?;
+                require!(
+                    candidate.poll_id == poll_id && candidate.approved && candidate.candidate_index == choice as u64,
+                    ErrorCode::BallotCandidateMismatch
+                );
+                candidate.candidate_votes += 1;
+                candidate.exit(&crate::ID)?;
+            }
+        }
+        poll.ballot_count += 1;
+
+        let ballot = &mut ctx.accounts.ballot;
+        ballot.poll_id = poll_id;
+        ballot.voter = ctx.accounts.signer.key();
+        ballot.choices = choices;
+        ballot.voted_at = now;
+        ballot.tallied_round = 0;
+
+        Ok(())
+    }
+
+    // Instant-runoff counting for ranked-choice polls. Each round every ballot is counted exactly once, for its
+    // highest-ranked candidate still in the race, through as many `tally_ballots` calls as it takes. Anyone can
+    // run the tally, and `finish_tally_round` refuses to close a round until all ballots of the poll are in it.
+    pub fn start_tally(ctx: Context<StartTally>, poll_id: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp as u64;
+        let poll = &ctx.accounts.poll;
+        require!(poll.poll_type == PollType::RankedChoice, ErrorCode::WrongPollType);
+        require!(now > poll.poll_end_time, ErrorCode::PollNotEnded);
+
+        let candidate_amount = poll.candidate_amount as usize;
+        let tally = &mut ctx.accounts.tally;
+        tally.poll_id = poll_id;
+        tally.round = 1;
+        tally.eliminated = vec![false; candidate_amount];
+        tally.counts = vec![0; candidate_amount];
+        tally.ballots_counted = 0;
+        tally.exhausted = 0;
+        tally.winner = None;
+        tally.finished = candidate_amount == 0;
+
+        Ok(())
+    }
+
+    pub fn tally_ballots<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TallyBallots<'info>>,
+        poll_id: u64,
+    ) -> Result<()> {
+        let tally = &mut ctx.accounts.tally;
+        require!(!tally.finished, ErrorCode::TallyFinished);
+
+        for ballot_info in ctx.remaining_accounts {
+            let mut ballot = Account::<Ballot>::try_from(ballot_info)?;
+            require_eq!(ballot.poll_id, poll_id, ErrorCode::InvalidBallot);
+            require!(ballot.tallied_round < tally.round, ErrorCode::BallotAlreadyTallied);
+            ballot.tallied_round = tally.round;
+
+            match ballot.choices.iter().find(|&&choice| !tally.eliminated[choice as usize]) {
+                Some(&choice) => tally.counts[choice as usize] += 1,
+                None => tally.exhausted += 1,
+            }
+            tally.ballots_counted += 1;
+            ballot.exit(&crate::ID)?;
+        }
+
+        Ok(())
+    }
+
+    // A candidate with a majority of the ballots still in play wins, as does the leader once only two candidates
+    // remain. Otherwise the candidate with the fewest votes is eliminated and the next round starts from zero.
+    // Ties go against the candidate registered last.
+    pub fn finish_tally_round(ctx: Context<FinishTallyRound>, _poll_id: u64) -> Result<()> {
+        let ballot_count = ctx.accounts.poll.ballot_count;
+        let tally = &mut ctx.accounts.tally;
+        require!(!tally.finished, ErrorCode::TallyFinished);
+        require_eq!(tally.ballots_counted, ballot_count, ErrorCode::RoundIncomplete);
+
+        let active_votes: u64 = tally.counts.iter().sum();
+        let remaining: Vec<usize> = (0..tally.counts.len()).filter(|&index| !tally.eliminated[index]).collect();
+        let leader = remaining
+            .iter()
+            .copied()
+            .max_by_key(|&index| (tally.counts[index], std::cmp::Reverse(index)))
+            .ok_or(ErrorCode::TallyFinished)?;
+
+        if active_votes == 0 {
+            tally.finished = true;
+        } else if tally.counts[leader] * 2 > active_votes || remaining.len() <= 2 {
+            tally.winner = Some(leader as u8);
+            tally.finished = true;
+        } else {
+            let loser = remaining
+                .iter()
+                .copied()
+                .min_by_key(|&index| (tally.counts[index], std::cmp::Reverse(index)))
+                .ok_or(ErrorCode::TallyFinished)?;
+            tally.eliminated[loser] = true;
+            tally.round += 1;
+            tally.counts.iter_mut().for_each(|count| *count = 0);
+            tally.ballots_counted = 0;
+            tally.exhausted = 0;
+        }
 
         Ok(())
     }
 }
 
+// A ballot needs at least one candidate, each known to the poll and listed once.
+fn validate_choices(choices: &[u8], candidate_amount: u64) -> Result<()> {
+    require!(!choices.is_empty(), ErrorCode::InvalidBallot);
+    for (position, &choice) in choices.iter().enumerate() {
+        require!((choice as u64) < candidate_amount, ErrorCode::InvalidBallot);
+        require!(!choices[..position].contains(&choice), ErrorCode::InvalidBallot);
+    }
+    Ok(())
+}
+
+// Transfer hooks need extra accounts that neither `vote_weighted` nor `withdraw_vote_tokens` take.
+fn has_transfer_hook(mint: &AccountInfo) -> Result<bool> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(false);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(mint_state.get_extension::<TransferHook>().is_ok())
+}
+
+// Transfer fees withheld in a Token-2022 account, which have to be harvested before it can be closed.
+fn withheld_transfer_fees(token_account: &AccountInfo) -> Result<u64> {
+    if *token_account.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+    let data = token_account.try_borrow_data()?;
+    let account_state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    Ok(account_state
+        .get_extension::<TransferFeeAmount>()
+        .map_or(0, |transfer_fee_amount| transfer_fee_amount.withheld_amount.into()))
+}
+
 #[derive(Accounts)]
 #[instruction(candidate_name: String, poll_id: u64)]
 pub struct Vote<'info> {
-    pub signer: Signer<'info>,  
+    #[account(mut)]
+    pub signer: Signer<'info>,
 
     #[account(
         mut,
@@ -66,18 +395,255 @@ pub struct Vote<'info> {
         bump, // Calculated by the runtime.
     )]
     pub poll: Account<'info, Poll>,
+
+    // Only one receipt can exist per voter and poll, so a second vote fails to create it.
+    #[account(
+        init,
+        payer = signer,
+        space = ANCHOR_DISCRIMINATOR + VoterReceipt::INIT_SPACE,
+        seeds = [b"receipt", poll_id.to_le_bytes().as_ref(), signer.key().as_ref()],
+        bump, // Calculated by the runtime.
+    )]
+    pub voter_receipt: Account<'info, VoterReceipt>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(candidate_name: String, poll_id: u64)]
+pub struct VoteWeighted<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref(), candidate_name.as_bytes()],
+        bump, // Calculated by the runtime.
+    )]
+    pub candidate: Account<'info, Candidate>,
+
+    #[account(
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump, // Calculated by the runtime.
+        constraint = poll.governance_mint == Some(governance_mint.key()) @ ErrorCode::GovernanceMintMismatch,
+    )]
+    pub poll: Account<'info, Poll>,
+
+    pub governance_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = governance_mint,
+        token::authority = signer,
+        token::token_program = token_program,
+    )]
+    pub voter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = ANCHOR_DISCRIMINATOR + VoterReceipt::INIT_SPACE,
+        seeds = [b"receipt", poll_id.to_le_bytes().as_ref(), signer.key().as_ref()],
+        bump, // Calculated by the runtime.
+    )]
+    pub voter_receipt: Account<'info, VoterReceipt>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"escrow", poll_id.to_le_bytes().as_ref(), signer.key().as_ref()],
+        bump, // Calculated by the runtime.
+        token::mint = governance_mint,
+        token::authority = poll,
+        token::token_program = token_program,
+    )]
+    pub vote_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct WithdrawVoteTokens<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump, // Calculated by the runtime.
+        constraint = poll.governance_mint == Some(governance_mint.key()) @ ErrorCode::GovernanceMintMismatch,
+    )]
+    pub poll: Account<'info, Poll>,
+
+    // Takes the transfer fees withheld in the escrow.
+    #[account(mut)]
+    pub governance_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = governance_mint,
+        token::authority = signer,
+        token::token_program = token_program,
+    )]
+    pub voter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", poll_id.to_le_bytes().as_ref(), signer.key().as_ref()],
+        bump, // Calculated by the runtime.
+    )]
+    pub vote_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Proof that `voter` took part in the poll, and which candidate they chose.
+#[account]
+#[derive(InitSpace)]
+pub struct VoterReceipt {
+    pub poll_id: u64,
+    pub voter: Pubkey,
+    pub candidate: Pubkey,
+    pub voted_at: u64,
+    // 1 for regular polls, the escrowed token amount for token-weighted ones.
+    pub weight: u64,
 }
 
 
 #[account]
 #[derive(InitSpace)]
 pub struct Poll {
+    pub authority: Pubkey,
     pub poll_id: u64,
     #[max_len(280)]
     pub poll_description: String,
     pub poll_start_time: u64,
     pub poll_end_time: u64,
     pub candidate_amount: u64,
+    pub allow_self_nomination: bool,
+    pub governance_mint: Option<Pubkey>,
+    pub poll_type: PollType,
+    pub ballot_count: u64,
+}
+
+impl Poll {
+    pub fn has_room_for_candidate(&self) -> bool {
+        self.poll_type == PollType::SingleChoice || self.candidate_amount < MAX_BALLOT_CANDIDATES as u64
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum PollType {
+    // One `vote` per voter for a single candidate.
+    SingleChoice,
+    // One ballot per voter approving any subset of candidates.
+    Approval,
+    // One ballot per voter ranking candidates, decided by instant runoff.
+    RankedChoice,
+}
+
+// An approval or ranked-choice ballot. `choices` holds candidate indexes, in order of preference for ranked ballots.
+#[account]
+#[derive(InitSpace)]
+pub struct Ballot {
+    pub poll_id: u64,
+    pub voter: Pubkey,
+    #[max_len(MAX_BALLOT_CANDIDATES)]
+    pub choices: Vec<u8>,
+    pub voted_at: u64,
+    // Last instant-runoff round this ballot was counted in.
+    pub tallied_round: u8,
+}
+
+// Progress of the instant-runoff count of a ranked-choice poll, indexed by candidate index.
+#[account]
+#[derive(InitSpace)]
+pub struct Tally {
+    pub poll_id: u64,
+    pub round: u8,
+    #[max_len(MAX_BALLOT_CANDIDATES)]
+    pub eliminated: Vec<bool>,
+    #[max_len(MAX_BALLOT_CANDIDATES)]
+    pub counts: Vec<u64>,
+    pub ballots_counted: u64,
+    pub exhausted: u64,
+    pub winner: Option<u8>,
+    pub finished: bool,
+}
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct CastBallot<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump, // Calculated by the runtime.
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = ANCHOR_DISCRIMINATOR + Ballot::INIT_SPACE,
+        seeds = [b"ballot", poll_id.to_le_bytes().as_ref(), signer.key().as_ref()],
+        bump, // Calculated by the runtime.
+    )]
+    pub ballot: Account<'info, Ballot>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct StartTally<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump, // Calculated by the runtime.
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = ANCHOR_DISCRIMINATOR + Tally::INIT_SPACE,
+        seeds = [b"tally", poll_id.to_le_bytes().as_ref()],
+        bump, // Calculated by the runtime.
+    )]
+    pub tally: Account<'info, Tally>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct TallyBallots<'info> {
+    #[account(
+        mut,
+        seeds = [b"tally", poll_id.to_le_bytes().as_ref()],
+        bump, // Calculated by the runtime.
+    )]
+    pub tally: Account<'info, Tally>,
+}
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct FinishTallyRound<'info> {
+    #[account(
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump, // Calculated by the runtime.
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        mut,
+        seeds = [b"tally", poll_id.to_le_bytes().as_ref()],
+        bump, // Calculated by the runtime.
+    )]
+    pub tally: Account<'info, Tally>,
 }
 
 #[derive(Accounts)]
@@ -95,6 +661,7 @@ pub struct InitializePoll<'info> {
 
     )]
     pub poll: Account<'info, Poll>,
+    pub governance_mint: Option<InterfaceAccount<'info, Mint>>,
     pub system_program: Program<'info, System>,
 }
 
@@ -106,6 +673,9 @@ pub struct Candidate {
     pub candidate_name: String,
     pub poll_id: u64,
     pub candidate_votes: u64,
+    pub approved: bool,
+    // Position among the approved candidates of the poll, used by approval and ranked-choice ballots.
+    pub candidate_index: u64,
 }
 
 #[derive(Accounts)]
@@ -127,7 +697,98 @@ pub struct InitializeCandidate<'info> {
         mut,
         seeds = [poll_id.to_le_bytes().as_ref()],
         bump, // Calculated by the runtime.
+        constraint = poll.authority == signer.key() @ ErrorCode::NotPollAuthority,
     )]
     pub poll: Account<'info, Poll>,
     pub system_program: Program<'info, System>,
-}
\ No newline at end of file
+}
+
+#[derive(Accounts)]
+#[instruction(candidate_name: String, poll_id: u64)]
+pub struct NominateCandidate<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = ANCHOR_DISCRIMINATOR + Candidate::INIT_SPACE,
+        seeds = [poll_id.to_le_bytes().as_ref(), candidate_name.as_bytes()],
+        bump, // Calculated by the runtime.
+    )]
+    pub candidate: Account<'info, Candidate>,
+
+    #[account(
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump, // Calculated by the runtime.
+    )]
+    pub poll: Account<'info, Poll>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(candidate_name: String, poll_id: u64)]
+pub struct ApproveCandidate<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref(), candidate_name.as_bytes()],
+        bump, // Calculated by the runtime.
+    )]
+    pub candidate: Account<'info, Candidate>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump, // Calculated by the runtime.
+        constraint = poll.authority == signer.key() @ ErrorCode::NotPollAuthority,
+    )]
+    pub poll: Account<'info, Poll>,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Poll end time is before its start time")]
+    InvalidPollWindow,
+    #[msg("Poll has not started yet")]
+    PollNotStarted,
+    #[msg("Poll has already ended")]
+    PollEnded,
+    #[msg("Only the poll authority can do this")]
+    NotPollAuthority,
+    #[msg("Candidates can only be added before the poll starts")]
+    CandidateRegistrationClosed,
+    #[msg("This poll does not accept self-nominations")]
+    SelfNominationDisabled,
+    #[msg("Candidate has not been approved by the poll authority")]
+    CandidateNotApproved,
+    #[msg("Candidate is already approved")]
+    CandidateAlreadyApproved,
+    #[msg("This poll is token-weighted, vote with vote_weighted")]
+    TokenWeightedPoll,
+    #[msg("Mint is not the governance mint of this poll")]
+    GovernanceMintMismatch,
+    #[msg("Voter holds no governance tokens")]
+    NoVotingPower,
+    #[msg("Poll has not ended yet")]
+    PollNotEnded,
+    #[msg("This instruction does not apply to this type of poll")]
+    WrongPollType,
+    #[msg("Only single-choice polls can be token-weighted")]
+    WeightedBallotsUnsupported,
+    #[msg("Approval and ranked-choice polls are limited to 16 candidates")]
+    CandidateLimitReached,
+    #[msg("Ballot must list known candidates, each at most once")]
+    InvalidBallot,
+    #[msg("Candidate accounts do not match the ballot")]
+    BallotCandidateMismatch,
+    #[msg("Ballot was already counted in this round")]
+    BallotAlreadyTallied,
+    #[msg("Not every ballot has been counted in this round")]
+    RoundIncomplete,
+    #[msg("Tally is already finished")]
+    TallyFinished,
+    #[msg("Governance mints with a transfer hook are not supported")]
+    TransferHookUnsupported,
+}
@@ -1,11 +1,30 @@
-import * as anchor from '@coral-xyz/anchor'
-import {Keypair} from '@solana/web3.js'
+import {Keypair, SystemProgram, Transaction, TransactionInstruction} from '@solana/web3.js'
 import { Voting } from '../target/types/voting'
-import { startAnchor } from "solana-bankrun";
+import { Clock, ProgramTestContext, startAnchor } from "solana-bankrun";
 import { BankrunProvider } from "anchor-bankrun";
+import NodeWallet from "@coral-xyz/anchor/dist/cjs/nodewallet";
 
 import { PublicKey } from "@solana/web3.js";
-import { AnchorProvider, BN, Program, setProvider, workspace } from "@coral-xyz/anchor";
+import {
+  ACCOUNT_SIZE,
+  AccountLayout,
+  AccountState,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createInitializeTransferHookInstruction,
+  createMintToInstruction,
+  ExtensionType,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  getTransferFeeConfig,
+  MINT_SIZE,
+  MintLayout,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  unpackMint,
+} from "@solana/spl-token";
+import { BN, Program, setProvider } from "@coral-xyz/anchor";
 const IDL = require("../target/idl/voting.json");
 
 const votingAddress = new PublicKey(
@@ -13,28 +32,92 @@ const votingAddress = new PublicKey(
 );
 
 describe("voting-dapp", () => {
-  let context;
-  let provider;
-  setProvider(AnchorProvider.env());
-  let votingProgram = workspace.Voting as Program<Voting>;
-  // let votingProgram: Program<Voting>;
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let votingProgram: Program<Voting>;
+
+  // Candidates can only be added before a poll starts, so poll 1 opens shortly after the tests begin.
+  let pollStartTime: number;
+
+  const now = async () => Number((await context.banksClient.getClock()).unixTimestamp);
+
+  const warpTo = async (unixTimestamp: number) => {
+    const clock = await context.banksClient.getClock();
+    context.setClock(
+      new Clock(
+        clock.slot,
+        clock.epochStartTimestamp,
+        clock.epoch,
+        clock.leaderScheduleEpoch,
+        BigInt(unixTimestamp),
+      )
+    );
+  }
+
+  const fundedVoter = () => {
+    const voter = Keypair.generate();
+    context.setAccount(voter.publicKey, {
+      lamports: 1_000_000_000,
+      data: Buffer.alloc(0),
+      owner: SystemProgram.programId,
+      executable: false,
+    });
+    return voter;
+  }
+
+  // Bankrun signs every transaction with the same blockhash, so sending an identical transaction
+  // again is dropped as a duplicate. Retries go through another fee payer to get a new signature.
+  const programPaidBy = (feePayer: Keypair) => {
+    const feePayerProvider = new BankrunProvider(context);
+    feePayerProvider.wallet = new NodeWallet(feePayer);
+    return new Program<Voting>(IDL, feePayerProvider);
+  }
+
+  // A Token-2022 mint of `payer`, with `extensions` set up by `extensionInstructions`.
+  const createToken2022Mint = async (
+    extensions: ExtensionType[],
+    extensionInstructions: (mint: PublicKey) => TransactionInstruction[],
+  ) => {
+    const payer = context.payer;
+    const mint = Keypair.generate();
+    await provider.sendAndConfirm!(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: payer.publicKey,
+          newAccountPubkey: mint.publicKey,
+          space: getMintLen(extensions),
+          lamports: 1_000_000_000,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        ...extensionInstructions(mint.publicKey),
+        createInitializeMintInstruction(mint.publicKey, 0, payer.publicKey, null, TOKEN_2022_PROGRAM_ID),
+      ),
+      [mint],
+    );
+    return mint.publicKey;
+  }
 
   beforeAll(async () => {
-    // context = await startAnchor("", [{name: "voting", programId: votingAddress}], []);
-    // provider = new BankrunProvider(context);
-
-    // votingProgram = new Program<Voting>(
-    //   IDL,
-    //   provider
-    // ) as Program<Voting>;
+    context = await startAnchor("", [], []);
+    provider = new BankrunProvider(context);
+    setProvider(provider);
+
+    votingProgram = new Program<Voting>(
+      IDL,
+      provider
+    );
   })
 
   it("Initialize Poll", async () => {
+    pollStartTime = (await now()) + 10;
+
     await votingProgram.methods.initializePoll(
       new BN(1),
       "what is your fav type of fruit?",
-      new BN(0),
+      new BN(pollStartTime),
       new BN(1823549207),
+      false,
+      { singleChoice: {} },
     ).rpc()
 
     const [pollAddress] = PublicKey.findProgramAddressSync(
@@ -50,6 +133,8 @@ describe("voting-dapp", () => {
 
     expect(poll.pollId.toNumber()).toEqual(1);
 
+    expect(poll.authority).toEqual(votingProgram.provider.publicKey);
+
     expect(poll.candidateAmount.toNumber()).toEqual(0);
 
     expect(poll.pollEndTime.toNumber()).toBeGreaterThanOrEqual(poll.pollStartTime.toNumber());
@@ -98,6 +183,8 @@ describe("voting-dapp", () => {
   
   it("Vote", async () => {
 
+    await warpTo(pollStartTime);
+
     await votingProgram.methods.vote(
       "Crunchy",
       new BN(1),
@@ -115,8 +202,580 @@ describe("voting-dapp", () => {
 
     expect(crunchy.candidateVotes.toNumber()).toEqual(1);
 
-    
+    const [receiptAddress] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), new BN(1).toArrayLike(Buffer,'le', 8), votingProgram.provider.publicKey.toBuffer()],
+      votingAddress,
+    )
+
+    const receipt = await votingProgram.account.voterReceipt.fetch(receiptAddress)
+
+    expect(receipt.pollId.toNumber()).toEqual(1);
+    expect(receipt.voter).toEqual(votingProgram.provider.publicKey);
+    expect(receipt.candidate).toEqual(crunchyAddress);
+    expect(receipt.votedAt.toNumber()).toEqual(pollStartTime);
+
+  });
+
+  it("Rejects a second vote from the same wallet", async () => {
+
+    const [receiptAddress] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), new BN(1).toArrayLike(Buffer,'le', 8), votingProgram.provider.publicKey.toBuffer()],
+      votingAddress,
+    )
+
+    const tx = await votingProgram.methods.vote(
+      "Smooth",
+      new BN(1),
+    ).transaction();
+    tx.recentBlockhash = context.lastBlockhash;
+    tx.feePayer = context.payer.publicKey;
+    tx.sign(context.payer);
+    const result = await context.banksClient.tryProcessTransaction(tx);
+
+    // The receipt of the first vote already sits at its address, so the system program refuses
+    // to create it again with AccountAlreadyInUse (custom error 0).
+    expect(result.result).toMatch(/custom program error: 0x0/);
+    expect(result.meta!.logMessages).toContainEqual(
+      expect.stringMatching(new RegExp(`${receiptAddress.toBase58()}.*already in use`))
+    );
+
+    const [smoothAddress] = PublicKey.findProgramAddressSync(
+      [new BN(1).toArrayLike(Buffer,'le', 8), Buffer.from("Smooth")],
+      votingAddress,
+    )
+
+    const smooth = await votingProgram.account.candidate.fetch(smoothAddress)
+
+    expect(smooth.candidateVotes.toNumber()).toEqual(0);
+
+  });
+
+  it("Rejects a poll that ends before it starts", async () => {
+
+    await expect(
+      votingProgram.methods.initializePoll(
+        new BN(2),
+        "what is your fav type of vegetable?",
+        new BN(1823549207),
+        new BN(0),
+        false,
+        { singleChoice: {} },
+      ).rpc()
+    ).rejects.toThrow(/InvalidPollWindow/);
+
+  });
+
+  it("Rejects a vote before the poll starts", async () => {
+
+    await votingProgram.methods.initializePoll(
+      new BN(3),
+      "what is your fav type of bread?",
+      new BN(1823549207),
+      new BN(1823549307),
+      false,
+      { singleChoice: {} },
+    ).rpc()
+
+    await votingProgram.methods.initializeCandidate(
+      "Sourdough",
+      new BN(3),
+    ).rpc()
+
+    await expect(
+      votingProgram.methods.vote(
+        "Sourdough",
+        new BN(3),
+      ).rpc()
+    ).rejects.toThrow(/PollNotStarted/);
+
+  });
+
+  it("Rejects a vote after the poll ends", async () => {
+
+    const startTime = (await now()) + 10;
+    const endTime = startTime + 10;
+
+    await votingProgram.methods.initializePoll(
+      new BN(8),
+      "what is your fav type of cheese?",
+      new BN(startTime),
+      new BN(endTime),
+      false,
+      { singleChoice: {} },
+    ).rpc()
+
+    await votingProgram.methods.initializeCandidate(
+      "Gouda",
+      new BN(8),
+    ).rpc()
+
+    await warpTo(endTime + 1);
+
+    await expect(
+      votingProgram.methods.vote(
+        "Gouda",
+        new BN(8),
+      ).rpc()
+    ).rejects.toThrow(/PollEnded/);
+
+  });
+
+  it("Rejects candidates once the poll has started", async () => {
+
+    await expect(
+      votingProgram.methods.initializeCandidate(
+        "Mango",
+        new BN(1),
+      ).rpc()
+    ).rejects.toThrow(/CandidateRegistrationClosed/);
+
+  });
+
+  it("Only the authority adds candidates and approves nominations", async () => {
+
+    const nominee = fundedVoter();
+
+    await votingProgram.methods.initializePoll(
+      new BN(4),
+      "what is your fav type of cheese?",
+      new BN(1823549207),
+      new BN(1823549307),
+      true,
+      { singleChoice: {} },
+    ).rpc()
+
+    await expect(
+      votingProgram.methods.initializeCandidate(
+        "Brie",
+        new BN(4),
+      )
+        .accounts({ signer: nominee.publicKey })
+        .signers([nominee])
+        .rpc()
+    ).rejects.toThrow(/NotPollAuthority/);
+
+    await votingProgram.methods.nominateCandidate(
+      "Brie",
+      new BN(4),
+    )
+      .accounts({ signer: nominee.publicKey })
+      .signers([nominee])
+      .rpc()
+
+    const [brieAddress] = PublicKey.findProgramAddressSync(
+      [new BN(4).toArrayLike(Buffer,'le', 8), Buffer.from("Brie")],
+      votingAddress,
+    )
+    const [pollAddress] = PublicKey.findProgramAddressSync(
+      [new BN(4).toArrayLike(Buffer, "le", 8)],
+      votingAddress,
+    )
+
+    expect((await votingProgram.account.candidate.fetch(brieAddress)).approved).toEqual(false);
+    expect((await votingProgram.account.poll.fetch(pollAddress)).candidateAmount.toNumber()).toEqual(0);
+
+    await expect(
+      votingProgram.methods.approveCandidate(
+        "Brie",
+        new BN(4),
+      )
+        .accounts({ signer: nominee.publicKey })
+        .signers([nominee])
+        .rpc()
+    ).rejects.toThrow(/NotPollAuthority/);
+
+    await votingProgram.methods.approveCandidate(
+      "Brie",
+      new BN(4),
+    ).rpc()
+
+    expect((await votingProgram.account.candidate.fetch(brieAddress)).approved).toEqual(true);
+    expect((await votingProgram.account.poll.fetch(pollAddress)).candidateAmount.toNumber()).toEqual(1);
+
+  });
+
+  it("Rejects nominations when the poll does not allow them", async () => {
+
+    await expect(
+      votingProgram.methods.nominateCandidate(
+        "Rye",
+        new BN(3),
+      ).rpc()
+    ).rejects.toThrow(/SelfNominationDisabled/);
+
+  });
+
+  it("Weights votes by escrowed governance tokens", async () => {
+
+    const payer = context.payer;
+    const governanceMint = Keypair.generate().publicKey;
+    const mintData = Buffer.alloc(MINT_SIZE);
+    MintLayout.encode({
+      mintAuthorityOption: 1,
+      mintAuthority: payer.publicKey,
+      supply: BigInt(100),
+      decimals: 0,
+      isInitialized: true,
+      freezeAuthorityOption: 0,
+      freezeAuthority: PublicKey.default,
+    }, mintData);
+    context.setAccount(governanceMint, {
+      lamports: 1_000_000_000,
+      data: mintData,
+      owner: TOKEN_PROGRAM_ID,
+      executable: false,
+    });
+
+    const voterTokenAccount = getAssociatedTokenAddressSync(governanceMint, payer.publicKey);
+    const tokenAccountData = Buffer.alloc(ACCOUNT_SIZE);
+    AccountLayout.encode({
+      mint: governanceMint,
+      owner: payer.publicKey,
+      amount: BigInt(100),
+      delegateOption: 0,
+      delegate: PublicKey.default,
+      state: AccountState.Initialized,
+      isNativeOption: 0,
+      isNative: BigInt(0),
+      delegatedAmount: BigInt(0),
+      closeAuthorityOption: 0,
+      closeAuthority: PublicKey.default,
+    }, tokenAccountData);
+    context.setAccount(voterTokenAccount, {
+      lamports: 1_000_000_000,
+      data: tokenAccountData,
+      owner: TOKEN_PROGRAM_ID,
+      executable: false,
+    });
+    const tokenBalance = async () =>
+      AccountLayout.decode((await context.banksClient.getAccount(voterTokenAccount))!.data).amount;
+
+    const startTime = (await now()) + 10;
+    const endTime = startTime + 10;
+
+    await votingProgram.methods.initializePoll(
+      new BN(5),
+      "what is your fav type of pasta?",
+      new BN(startTime),
+      new BN(endTime),
+      false,
+      { singleChoice: {} },
+    )
+      .accounts({ governanceMint })
+      .rpc()
+
+    await votingProgram.methods.initializeCandidate(
+      "Penne",
+      new BN(5),
+    ).rpc()
+
+    await warpTo(startTime);
+
+    await expect(
+      votingProgram.methods.vote(
+        "Penne",
+        new BN(5),
+      ).rpc()
+    ).rejects.toThrow(/TokenWeightedPoll/);
+
+    await votingProgram.methods.voteWeighted(
+      "Penne",
+      new BN(5),
+    )
+      .accounts({
+        governanceMint,
+        voterTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc()
+
+    const [penneAddress] = PublicKey.findProgramAddressSync(
+      [new BN(5).toArrayLike(Buffer,'le', 8), Buffer.from("Penne")],
+      votingAddress,
+    )
+    const [receiptAddress] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), new BN(5).toArrayLike(Buffer,'le', 8), payer.publicKey.toBuffer()],
+      votingAddress,
+    )
+
+    expect((await votingProgram.account.candidate.fetch(penneAddress)).candidateVotes.toNumber()).toEqual(100);
+    expect((await votingProgram.account.voterReceipt.fetch(receiptAddress)).weight.toNumber()).toEqual(100);
+    expect(await tokenBalance()).toEqual(BigInt(0));
+
+    await expect(
+      programPaidBy(fundedVoter()).methods.withdrawVoteTokens(
+        new BN(5),
+      )
+        .accounts({
+          signer: payer.publicKey,
+          governanceMint,
+          voterTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([payer])
+        .rpc()
+    ).rejects.toThrow(/PollNotEnded/);
+
+    await warpTo(endTime + 1);
+
+    await votingProgram.methods.withdrawVoteTokens(
+      new BN(5),
+    )
+      .accounts({
+        governanceMint,
+        voterTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc()
+
+    expect(await tokenBalance()).toEqual(BigInt(100));
+
+  });
+
+  const candidateAddress = (pollId: number, name: string) => PublicKey.findProgramAddressSync(
+    [new BN(pollId).toArrayLike(Buffer,'le', 8), Buffer.from(name)],
+    votingAddress,
+  )[0]
+
+  const ballotAddress = (pollId: number, voter: PublicKey) => PublicKey.findProgramAddressSync(
+    [Buffer.from("ballot"), new BN(pollId).toArrayLike(Buffer,'le', 8), voter.toBuffer()],
+    votingAddress,
+  )[0]
+
+  it("Counts approval ballots for every approved candidate", async () => {
+
+    const startTime = (await now()) + 10;
+
+    await votingProgram.methods.initializePoll(
+      new BN(6),
+      "which snacks should we order?",
+      new BN(startTime),
+      new BN(1823549207),
+      false,
+      { approval: {} },
+    ).rpc()
+
+    for (const name of ["Chips", "Nuts", "Olives"]) {
+      await votingProgram.methods.initializeCandidate(name, new BN(6)).rpc()
+    }
+
+    await warpTo(startTime);
+
+    await expect(
+      votingProgram.methods.vote("Chips", new BN(6)).rpc()
+    ).rejects.toThrow(/WrongPollType/);
+
+    await votingProgram.methods.castBallot(new BN(6), Buffer.from([0, 2]))
+      .remainingAccounts([
+        { pubkey: candidateAddress(6, "Chips"), isWritable: true, isSigner: false },
+        { pubkey: candidateAddress(6, "Olives"), isWritable: true, isSigner: false },
+      ])
+      .rpc()
+
+    const voter = fundedVoter();
+    await expect(
+      votingProgram.methods.castBallot(new BN(6), Buffer.from([1, 1]))
+        .accounts({ signer: voter.publicKey })
+        .remainingAccounts([
+          { pubkey: candidateAddress(6, "Nuts"), isWritable: true, isSigner: false },
+          { pubkey: candidateAddress(6, "Nuts"), isWritable: true, isSigner: false },
+        ])
+        .signers([voter])
+        .rpc()
+    ).rejects.toThrow(/InvalidBallot/);
+
+    const votes = async (name: string) =>
+      (await votingProgram.account.candidate.fetch(candidateAddress(6, name))).candidateVotes.toNumber();
+
+    expect(await votes("Chips")).toEqual(1);
+    expect(await votes("Nuts")).toEqual(0);
+    expect(await votes("Olives")).toEqual(1);
+
+    const ballot = await votingProgram.account.ballot.fetch(ballotAddress(6, votingProgram.provider.publicKey));
+    expect(Array.from(ballot.choices)).toEqual([0, 2]);
+
+  });
+
+  it("Decides ranked-choice polls by instant runoff", async () => {
+
+    const voters = [];
+    for (let i = 0; i < 5; i++) {
+      voters.push(fundedVoter());
+    }
+
+    const startTime = (await now()) + 10;
+    const endTime = startTime + 15;
+
+    await votingProgram.methods.initializePoll(
+      new BN(7),
+      "where should the offsite be?",
+      new BN(startTime),
+      new BN(endTime),
+      false,
+      { rankedChoice: {} },
+    ).rpc()
+
+    for (const name of ["Alps", "Beach", "City"]) {
+      await votingProgram.methods.initializeCandidate(name, new BN(7)).rpc()
+    }
+
+    await warpTo(startTime);
+
+    // Alps and City tie on first preferences; Beach is eliminated and its voter's second choice decides for City.
+    const rankings = [[0], [0], [1, 2], [2, 1], [2, 1]];
+    for (const [i, ranking] of rankings.entries()) {
+      await votingProgram.methods.castBallot(new BN(7), Buffer.from(ranking))
+        .accounts({ signer: voters[i].publicKey })
+        .signers([voters[i]])
+        .rpc()
+    }
+
+    await warpTo(endTime + 1);
+
+    await votingProgram.methods.startTally(new BN(7)).rpc()
+
+    const ballots = voters.map((voter) => ({
+      pubkey: ballotAddress(7, voter.publicKey),
+      isWritable: true,
+      isSigner: false,
+    }));
+
+    const [tallyAddress] = PublicKey.findProgramAddressSync(
+      [Buffer.from("tally"), new BN(7).toArrayLike(Buffer,'le', 8)],
+      votingAddress,
+    )
+
+    // Round one, counted in two chunks.
+    await votingProgram.methods.tallyBallots(new BN(7)).remainingAccounts(ballots.slice(0, 3)).rpc()
+
+    await expect(
+      programPaidBy(voters[0]).methods.finishTallyRound(new BN(7)).rpc()
+    ).rejects.toThrow(/RoundIncomplete/);
+
+    await expect(
+      votingProgram.methods.tallyBallots(new BN(7)).remainingAccounts(ballots.slice(2, 3)).rpc()
+    ).rejects.toThrow(/BallotAlreadyTallied/);
+
+    await votingProgram.methods.tallyBallots(new BN(7)).remainingAccounts(ballots.slice(3)).rpc()
+    await votingProgram.methods.finishTallyRound(new BN(7)).rpc()
+
+    let tally = await votingProgram.account.tally.fetch(tallyAddress);
+    expect(tally.finished).toEqual(false);
+    expect(tally.round).toEqual(2);
+    expect(tally.eliminated).toEqual([false, true, false]);
+
+    // Round two.
+    await votingProgram.methods.tallyBallots(new BN(7)).remainingAccounts(ballots).rpc()
+    await programPaidBy(voters[1]).methods.finishTallyRound(new BN(7)).rpc()
+
+    tally = await votingProgram.account.tally.fetch(tallyAddress);
+    expect(tally.finished).toEqual(true);
+    expect(tally.winner).toEqual(2);
+    expect(tally.counts.map((count) => count.toNumber())).toEqual([2, 0, 3]);
+
+  });
+
+  it("Returns escrowed tokens of a transfer-fee governance mint", async () => {
+
+    const payer = context.payer;
+    // 1% of every transfer is withheld in the receiving account.
+    const governanceMint = await createToken2022Mint(
+      [ExtensionType.TransferFeeConfig],
+      (mint) => [
+        createInitializeTransferFeeConfigInstruction(
+          mint, payer.publicKey, payer.publicKey, 100, BigInt(1_000), TOKEN_2022_PROGRAM_ID,
+        ),
+      ],
+    );
+    const voterTokenAccount = getAssociatedTokenAddressSync(governanceMint, payer.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    await provider.sendAndConfirm!(
+      new Transaction().add(
+        createAssociatedTokenAccountIdempotentInstruction(
+          payer.publicKey, voterTokenAccount, payer.publicKey, governanceMint, TOKEN_2022_PROGRAM_ID,
+        ),
+        createMintToInstruction(governanceMint, voterTokenAccount, payer.publicKey, 1_000, [], TOKEN_2022_PROGRAM_ID),
+      ),
+    );
+
+    const startTime = (await now()) + 10;
+    const endTime = startTime + 10;
+
+    await votingProgram.methods.initializePoll(
+      new BN(9),
+      "what is your fav type of pasta?",
+      new BN(startTime),
+      new BN(endTime),
+      false,
+      { singleChoice: {} },
+    )
+      .accounts({ governanceMint })
+      .rpc()
+
+    await votingProgram.methods.initializeCandidate(
+      "Fusilli",
+      new BN(9),
+    ).rpc()
+
+    await warpTo(startTime);
+
+    const weightedAccounts = {
+      governanceMint,
+      voterTokenAccount,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+    await votingProgram.methods.voteWeighted(
+      "Fusilli",
+      new BN(9),
+    )
+      .accounts(weightedAccounts)
+      .rpc()
+
+    // Only what reached the escrow counts.
+    expect((await votingProgram.account.candidate.fetch(candidateAddress(9, "Fusilli"))).candidateVotes.toNumber()).toEqual(990);
+
+    await warpTo(endTime + 1);
+
+    await votingProgram.methods.withdrawVoteTokens(
+      new BN(9),
+    )
+      .accounts(weightedAccounts)
+      .rpc()
+
+    // The fee withheld in the escrow went to the mint, so the escrow could be closed.
+    const [escrowAddress] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), new BN(9).toArrayLike(Buffer,'le', 8), payer.publicKey.toBuffer()],
+      votingAddress,
+    )
+    expect(await context.banksClient.getAccount(escrowAddress)).toBeNull();
+    const mintAccount = (await context.banksClient.getAccount(governanceMint))!;
+    const mint = unpackMint(governanceMint, { ...mintAccount, data: Buffer.from(mintAccount.data) }, TOKEN_2022_PROGRAM_ID);
+    expect(getTransferFeeConfig(mint)!.withheldAmount).toEqual(BigInt(10));
+
+  });
 
+  it("Rejects governance mints with a transfer hook", async () => {
+
+    const payer = context.payer;
+    const governanceMint = await createToken2022Mint(
+      [ExtensionType.TransferHook],
+      (mint) => [
+        createInitializeTransferHookInstruction(mint, payer.publicKey, Keypair.generate().publicKey, TOKEN_2022_PROGRAM_ID),
+      ],
+    );
+    const startTime = (await now()) + 10;
+
+    await expect(
+      votingProgram.methods.initializePoll(
+        new BN(10),
+        "what is your fav type of pasta?",
+        new BN(startTime),
+        new BN(startTime + 10),
+        false,
+        { singleChoice: {} },
+      )
+        .accounts({ governanceMint })
+        .rpc()
+    ).rejects.toThrow(/TransferHookUnsupported/);
 
   });
 });